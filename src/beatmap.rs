@@ -1,23 +1,36 @@
-use std::{io, path::Path, str::FromStr};
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    io, mem,
+    ops::{Deref, DerefMut},
+    path::Path,
+    str::FromStr,
+};
 
 use crate::{
-    decode::{DecodeBeatmap, DecodeState},
+    decode::{DecodeBeatmap, DecodeOptions, DecodeState},
     section::{
         colors::{Color, Colors, ColorsState, CustomColor, ParseColorsError},
+        difficulty::{self, Difficulty, DifficultyRef},
         editor::{Editor, EditorState, ParseEditorError},
-        events::BreakPeriod,
-        general::{CountdownType, GameMode},
+        events::{BreakPeriod, Events, StoryboardFileRef},
+        general::{CountdownType, GameMode, General},
         hit_objects::{
-            hit_samples::SampleBank, HitObject, HitObjects, HitObjectsState, ParseHitObjectsError,
+            decode::slider_velocity_at, hit_samples::SampleBank, stacking, CurveBuffers, HitObject,
+            HitObjectKind, HitObjects, HitObjectsState, ParseHitObjectsError, SliderEventType,
+        },
+        metadata::{unicode_or_ascii, Metadata, MetadataRef, MetadataState, ParseMetadataError},
+        timing_points::{
+            ControlPoints, DifficultyPoint, EffectPoint, SamplePoint, TimingPoint, TimingPoints,
         },
-        metadata::{Metadata, MetadataState, ParseMetadataError},
-        timing_points::ControlPoints,
     },
+    util::ApproxEq,
     LATEST_FORMAT_VERSION,
 };
 
 /// Fully parsed content of a `.osu` file.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Beatmap {
     pub format_version: i32,
 
@@ -67,6 +80,8 @@ pub struct Beatmap {
     // Events
     pub background_file: String,
     pub breaks: Vec<BreakPeriod>,
+    pub background_colors: Vec<(f64, Color)>,
+    pub storyboard_files: Vec<StoryboardFileRef>,
 
     // TimingPoints
     pub control_points: ControlPoints,
@@ -74,6 +89,8 @@ pub struct Beatmap {
     // Colors
     pub custom_combo_colors: Vec<Color>,
     pub custom_colors: Vec<CustomColor>,
+    pub slider_track_override: Option<Color>,
+    pub slider_border: Option<Color>,
 
     // HitObjects
     pub hit_objects: Vec<HitObject>,
@@ -119,6 +136,1342 @@ impl Beatmap {
     pub fn from_bytes(bytes: &[u8]) -> Result<Self, io::Error> {
         crate::from_bytes(bytes)
     }
+
+    /// Round `time` to the nearest beat snap of the beatmap's beat divisor.
+    ///
+    /// See [`ControlPoints::snap_time`].
+    pub fn snap_time(&self, time: f64) -> f64 {
+        self.control_points.snap_time(time, self.beat_divisor)
+    }
+
+    /// Returns the indices of all sliders in [`Beatmap::hit_objects`] whose
+    /// curve has a distance of zero or NaN.
+    ///
+    /// The curves are calculated with the given [`CurveBuffers`] if they have
+    /// not been accessed yet.
+    pub fn validate_slider_paths(&mut self, bufs: &mut CurveBuffers) -> Vec<usize> {
+        self.hit_objects
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(i, h)| match h.kind {
+                HitObjectKind::Slider(ref mut slider) => {
+                    let dist = slider.path.curve_with_bufs(bufs).dist();
+
+                    (dist == 0.0 || dist.is_nan()).then_some(i)
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Shift all timed elements of the beatmap by `delta` milliseconds.
+    ///
+    /// This affects the start time of [`HitObject`]s, the time of all
+    /// control points, [`BreakPeriod`]s, background color changes,
+    /// storyboard samples, bookmarks, and the preview time unless it is
+    /// unset, i.e. `-1`. Durations are relative to the start time so they
+    /// remain unchanged.
+    pub fn offset_time(&mut self, delta: f64) {
+        self.map_audio_times(|time| time + delta);
+
+        for h in self.hit_objects.iter_mut() {
+            h.start_time += delta;
+        }
+
+        let control_points = &mut self.control_points;
+
+        for point in control_points.timing_points.iter_mut() {
+            point.time += delta;
+        }
+
+        for point in control_points.difficulty_points.iter_mut() {
+            point.time += delta;
+        }
+
+        for point in control_points.effect_points.iter_mut() {
+            point.time += delta;
+        }
+
+        for point in control_points.sample_points.iter_mut() {
+            point.time += delta;
+        }
+
+        for break_period in self.breaks.iter_mut() {
+            break_period.start_time += delta;
+            break_period.end_time += delta;
+        }
+
+        for (time, _) in self.background_colors.iter_mut() {
+            *time += delta;
+        }
+
+        for file in self.storyboard_files.iter_mut() {
+            if let Some(ref mut time) = file.time {
+                *time += delta;
+            }
+        }
+    }
+
+    /// Speed up the beatmap by the given rate, e.g. `1.5` for the DT mod or
+    /// `0.75` for the HT mod.
+    ///
+    /// All times and durations that [`Beatmap::offset_time`] affects are
+    /// divided by `rate`, as well as the durations of spinners and hold
+    /// notes, and the beat length of [`TimingPoint`]s. The velocity of
+    /// sliders is recalculated so that their durations scale accordingly.
+    ///
+    /// Note that difficulty values such as AR and OD are *not* adjusted.
+    ///
+    /// `rate` is expected to be positive and finite.
+    pub fn apply_rate(&mut self, rate: f64) {
+        self.map_audio_times(|time| time / rate);
+
+        for h in self.hit_objects.iter_mut() {
+            h.start_time /= rate;
+
+            match h.kind {
+                HitObjectKind::Spinner(ref mut spinner) => spinner.duration /= rate,
+                HitObjectKind::Hold(ref mut hold) => hold.duration /= rate,
+                HitObjectKind::Circle(_) | HitObjectKind::Slider(_) => {}
+            }
+        }
+
+        let control_points = &mut self.control_points;
+
+        for point in control_points.timing_points.iter_mut() {
+            point.time /= rate;
+            point.beat_len /= rate;
+        }
+
+        for point in control_points.difficulty_points.iter_mut() {
+            point.time /= rate;
+        }
+
+        for point in control_points.effect_points.iter_mut() {
+            point.time /= rate;
+        }
+
+        for point in control_points.sample_points.iter_mut() {
+            point.time /= rate;
+        }
+
+        for break_period in self.breaks.iter_mut() {
+            break_period.start_time /= rate;
+            break_period.end_time /= rate;
+        }
+
+        for (time, _) in self.background_colors.iter_mut() {
+            *time /= rate;
+        }
+
+        for file in self.storyboard_files.iter_mut() {
+            if let Some(ref mut time) = file.time {
+                *time /= rate;
+            }
+        }
+
+        for h in self.hit_objects.iter_mut() {
+            if let HitObjectKind::Slider(ref mut slider) = h.kind {
+                slider.velocity = slider_velocity_at(
+                    h.start_time,
+                    &self.control_points,
+                    self.slider_multiplier,
+                    self.mode,
+                );
+            }
+        }
+    }
+
+    /// Apply `f` to the preview time, unless it is `-1`, and all bookmarks.
+    fn map_audio_times(&mut self, f: impl Fn(f64) -> f64) {
+        // Times are in milliseconds so they fit into `i32`
+        #[allow(clippy::cast_possible_truncation)]
+        let map = |time: i32| f(f64::from(time)).round() as i32;
+
+        if self.preview_time != -1 {
+            self.preview_time = map(self.preview_time);
+        }
+
+        for bookmark in self.bookmarks.iter_mut() {
+            *bookmark = map(*bookmark);
+        }
+    }
+
+    /// Cut the beatmap at the given time by removing all [`HitObject`]s that
+    /// start after it and calling [`ControlPoints::truncate_after`].
+    pub fn truncate(&mut self, time: f64) {
+        self.hit_objects.retain(|h| h.start_time <= time);
+        self.control_points.truncate_after(time);
+    }
+
+    /// Split the beatmap into the part up to `time` and the part after it.
+    ///
+    /// The first beatmap is the result of [`Beatmap::truncate`]. The second
+    /// beatmap contains the remaining [`HitObject`]s, control points, and
+    /// [`BreakPeriod`]s, shifted by `-time` so that it starts at zero. The
+    /// control points that are active at `time` are carried over to the
+    /// start of the second beatmap. Breaks that contain `time` are split as
+    /// well.
+    ///
+    /// Bookmarks and the preview time are assigned to the beatmap whose time
+    /// range contains them. The preview time is set to `-1` for the other.
+    pub fn split_at_time(mut self, time: f64) -> (Self, Self) {
+        let hit_objects = mem::take(&mut self.hit_objects);
+        let mut control_points = mem::take(&mut self.control_points);
+        let mut breaks = mem::take(&mut self.breaks);
+        let mut background_colors = mem::take(&mut self.background_colors);
+        let mut storyboard_files = mem::take(&mut self.storyboard_files);
+        let bookmarks = mem::take(&mut self.bookmarks);
+
+        // Only the remaining header fields are cloned
+        let mut after = self.clone();
+
+        let (before_objects, after_objects) =
+            hit_objects.into_iter().partition(|h| h.start_time <= time);
+
+        self.hit_objects = before_objects;
+        after.hit_objects = after_objects;
+
+        after.control_points = ControlPoints {
+            timing_points: split_off_active(&mut control_points.timing_points, time, |point| {
+                &mut point.time
+            }),
+            difficulty_points: split_off_active(
+                &mut control_points.difficulty_points,
+                time,
+                |point| &mut point.time,
+            ),
+            effect_points: split_off_active(&mut control_points.effect_points, time, |point| {
+                &mut point.time
+            }),
+            sample_points: split_off_active(&mut control_points.sample_points, time, |point| {
+                &mut point.time
+            }),
+        };
+
+        self.control_points = control_points;
+
+        after.background_colors = split_off_active(&mut background_colors, time, |(time, _)| time);
+
+        self.background_colors = background_colors;
+
+        after.breaks = breaks
+            .iter()
+            .filter(|b| b.end_time > time)
+            .map(|b| BreakPeriod {
+                start_time: b.start_time.max(time),
+                end_time: b.end_time,
+            })
+            .collect();
+
+        breaks.retain_mut(|b| {
+            b.end_time = b.end_time.min(time);
+
+            b.start_time < b.end_time
+        });
+
+        self.breaks = breaks;
+
+        after.storyboard_files = storyboard_files
+            .iter()
+            .filter(|file| file.time.is_none_or(|t| t > time))
+            .cloned()
+            .collect();
+
+        storyboard_files.retain(|file| file.time.is_none_or(|t| t <= time));
+        self.storyboard_files = storyboard_files;
+
+        (self.bookmarks, after.bookmarks) = bookmarks
+            .into_iter()
+            .partition(|bookmark| f64::from(*bookmark) <= time);
+
+        if self.preview_time != -1 {
+            if f64::from(self.preview_time) <= time {
+                after.preview_time = -1;
+            } else {
+                self.preview_time = -1;
+            }
+        }
+
+        after.offset_time(-time);
+
+        (self, after)
+    }
+
+    /// Merge control points of the same kind that share a time by only
+    /// keeping the last one, see [`ControlPoints::dedup_by_time`].
+    pub fn merge_duplicate_timing_points(&mut self) {
+        self.control_points.dedup_by_time();
+    }
+
+    /// The summed up duration of all [`BreakPeriod`]s.
+    ///
+    /// If `only_effective` is `true`, only breaks that
+    /// [have an effect](BreakPeriod::has_effect) are considered.
+    pub fn total_break_time(&self, only_effective: bool) -> f64 {
+        self.breaks
+            .iter()
+            .filter(|b| !only_effective || b.has_effect())
+            .map(BreakPeriod::duration)
+            .sum()
+    }
+
+    /// The minimum and maximum BPM of all [`TimingPoint`]s.
+    ///
+    /// Without any timing points, the BPM of [`TimingPoint::DEFAULT_BEAT_LEN`]
+    /// is used.
+    pub fn bpm_range(&self) -> (f64, f64) {
+        self.control_points
+            .timing_points
+            .iter()
+            .map(TimingPoint::bpm)
+            .fold(None, |range, bpm| match range {
+                Some((min, max)) => Some((bpm.min(min), bpm.max(max))),
+                None => Some((bpm, bpm)),
+            })
+            .unwrap_or_else(|| {
+                let bpm = TimingPoint::default().bpm();
+
+                (bpm, bpm)
+            })
+    }
+
+    /// The BPM that is used for the longest duration of the beatmap.
+    ///
+    /// Each [`TimingPoint`] lasts until the next one or until the end of the
+    /// last [`HitObject`]. The first timing point is considered to start at
+    /// time zero. Without any timing points, the BPM of
+    /// [`TimingPoint::DEFAULT_BEAT_LEN`] is used.
+    ///
+    /// If the last hit object is a slider whose curve has not yet been
+    /// accessed, it needs to be calculated first.
+    pub fn most_common_bpm(&mut self) -> f64 {
+        let Some(last_point) = self.control_points.timing_points.last() else {
+            return TimingPoint::default().bpm();
+        };
+
+        let fallback = last_point.bpm();
+
+        self.nth_most_common_bpm(0).unwrap_or(fallback)
+    }
+
+    /// The BPM with the `n`th longest duration, starting at `n = 0`.
+    ///
+    /// See [`Beatmap::bpm_histogram`].
+    pub fn nth_most_common_bpm(&mut self, n: usize) -> Option<f64> {
+        self.bpm_histogram().get(n).map(|(bpm, _)| *bpm)
+    }
+
+    /// The `(bpm, duration)` of each distinct BPM, sorted by the accumulated
+    /// duration in milliseconds in descending order.
+    ///
+    /// Each [`TimingPoint`] lasts until the next one or until the end of the
+    /// last [`HitObject`]. The first timing point is considered to start at
+    /// time zero. Without any timing points, the histogram is empty.
+    ///
+    /// If the last hit object is a slider whose curve has not yet been
+    /// accessed, it needs to be calculated first.
+    pub fn bpm_histogram(&mut self) -> Vec<(f64, f64)> {
+        let timing_points = &self.control_points.timing_points;
+
+        let Some(last_point) = timing_points.last() else {
+            return Vec::new();
+        };
+
+        let last_time = self
+            .hit_objects
+            .last_mut()
+            .map_or(last_point.time, HitObject::end_time);
+
+        let mut durations: Vec<(f64, f64)> = Vec::new();
+
+        for (i, point) in timing_points.iter().enumerate() {
+            if point.time > last_time {
+                continue;
+            }
+
+            let start_time = if i == 0 { 0.0 } else { point.time };
+            let end_time = timing_points.get(i + 1).map_or(last_time, |next| next.time);
+
+            // Round to prevent floating point inaccuracies from splitting
+            // the same beat length
+            let beat_len = (point.beat_len * 1000.0).round() / 1000.0;
+            let duration = end_time - start_time;
+
+            match durations
+                .iter_mut()
+                .find(|(len, _)| (*len - beat_len).abs() < f64::EPSILON)
+            {
+                Some((_, sum)) => *sum += duration,
+                None => durations.push((beat_len, duration)),
+            }
+        }
+
+        // Stable sort so that ties keep the earlier BPM first
+        durations.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+
+        durations
+            .into_iter()
+            .map(|(beat_len, duration)| (60_000.0 / beat_len, duration))
+            .collect()
+    }
+
+    /// The `(start_time, bpm)` of each [`TimingPoint`].
+    ///
+    /// Unlike [`ControlPoints::bpm_changes`], consecutive timing points with
+    /// the same BPM are all included. Without any timing points, the timeline
+    /// is empty.
+    pub fn bpm_timeline(&self) -> Vec<(f64, f64)> {
+        self.control_points
+            .timing_points
+            .iter()
+            .map(|point| (point.time, point.bpm()))
+            .collect()
+    }
+
+    /// The BPM as displayed in osu!'s song select.
+    ///
+    /// Returns a single rounded BPM or `"min-max"` if the rounded minimum and
+    /// maximum of [`Beatmap::bpm_range`] differ.
+    pub fn display_bpm(&self) -> String {
+        let (min, max) = self.bpm_range();
+        let (min, max) = (min.round(), max.round());
+
+        if (max - min).abs() < f64::EPSILON {
+            format!("{min}")
+        } else {
+            format!("{min}-{max}")
+        }
+    }
+
+    /// Returns a borrowed view on the `[Difficulty]` values without cloning
+    /// them into a [`Difficulty`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rosu_map::{section::difficulty::DifficultyRef, Beatmap};
+    ///
+    /// fn preempt_ms(difficulty: DifficultyRef<'_>) -> f64 {
+    ///     let ar = f64::from(*difficulty.approach_rate);
+    ///
+    ///     if ar > 5.0 {
+    ///         1200.0 - 750.0 * (ar - 5.0) / 5.0
+    ///     } else {
+    ///         1200.0 + 600.0 * (5.0 - ar) / 5.0
+    ///     }
+    /// }
+    ///
+    /// let map = Beatmap {
+    ///     approach_rate: 9.0,
+    ///     ..Default::default()
+    /// };
+    ///
+    /// assert_eq!(preempt_ms(map.difficulty_view()), 600.0);
+    /// ```
+    pub const fn difficulty_view(&self) -> DifficultyRef<'_> {
+        DifficultyRef {
+            hp_drain_rate: &self.hp_drain_rate,
+            circle_size: &self.circle_size,
+            overall_difficulty: &self.overall_difficulty,
+            approach_rate: &self.approach_rate,
+            slider_multiplier: &self.slider_multiplier,
+            slider_tick_rate: &self.slider_tick_rate,
+        }
+    }
+
+    /// The time in milliseconds that hit objects appear before their start
+    /// time, see [`Difficulty::ar_to_preempt_ms`].
+    pub fn ar_to_preempt_ms(&self) -> f64 {
+        difficulty::ar_to_preempt_ms(self.approach_rate)
+    }
+
+    /// The osu!standard hit window in milliseconds for a 300, see
+    /// [`Difficulty::od_to_great_ms`].
+    pub fn od_to_great_ms(&self) -> f64 {
+        difficulty::od_to_great_ms(self.overall_difficulty)
+    }
+
+    /// The osu!standard hit window in milliseconds for a 100, see
+    /// [`Difficulty::od_to_ok_ms`].
+    pub fn od_to_ok_ms(&self) -> f64 {
+        difficulty::od_to_ok_ms(self.overall_difficulty)
+    }
+
+    /// The osu!standard hit window in milliseconds for a 50, see
+    /// [`Difficulty::od_to_meh_ms`].
+    pub fn od_to_meh_ms(&self) -> f64 {
+        difficulty::od_to_meh_ms(self.overall_difficulty)
+    }
+
+    /// The osu!taiko hit window in milliseconds for a great hit, see
+    /// [`Difficulty::od_to_taiko_great_ms`].
+    pub fn od_to_taiko_great_ms(&self) -> f64 {
+        difficulty::od_to_taiko_great_ms(self.overall_difficulty)
+    }
+
+    /// The osu!taiko hit window in milliseconds for an ok hit, see
+    /// [`Difficulty::od_to_taiko_ok_ms`].
+    pub fn od_to_taiko_ok_ms(&self) -> f64 {
+        difficulty::od_to_taiko_ok_ms(self.overall_difficulty)
+    }
+
+    /// Returns a borrowed view on the `[Metadata]` values without cloning
+    /// them into a [`Metadata`].
+    pub fn metadata_view(&self) -> MetadataRef<'_> {
+        MetadataRef {
+            title: &self.title,
+            title_unicode: &self.title_unicode,
+            artist: &self.artist,
+            artist_unicode: &self.artist_unicode,
+            creator: &self.creator,
+            version: &self.version,
+            source: &self.source,
+            tags: &self.tags,
+            beatmap_id: &self.beatmap_id,
+            beatmap_set_id: &self.beatmap_set_id,
+        }
+    }
+
+    /// The title to display, i.e. [`Beatmap::title_unicode`] if it is
+    /// non-empty, otherwise [`Beatmap::title`].
+    pub fn display_title(&self) -> &str {
+        unicode_or_ascii(&self.title_unicode, &self.title)
+    }
+
+    /// The artist to display, i.e. [`Beatmap::artist_unicode`] if it is
+    /// non-empty, otherwise [`Beatmap::artist`].
+    pub fn display_artist(&self) -> &str {
+        unicode_or_ascii(&self.artist_unicode, &self.artist)
+    }
+
+    /// The `(width, height)` of the playfield in osu!pixels.
+    ///
+    /// For all modes but mania this is `(512.0, 384.0)`. For mania, the key
+    /// count, i.e. the rounded circle size, determines the width with each
+    /// column having a width of `80.0`.
+    pub fn playfield_size(&self) -> (f32, f32) {
+        const WIDTH: f32 = 512.0;
+        const HEIGHT: f32 = 384.0;
+        const MANIA_COLUMN_WIDTH: f32 = 80.0;
+
+        if self.mode.uses_columns() {
+            let key_count = self.circle_size.round().max(1.0);
+
+            (key_count * MANIA_COLUMN_WIDTH, HEIGHT)
+        } else {
+            (WIDTH, HEIGHT)
+        }
+    }
+
+    /// Returns a guard to mutate the [`HitObject`]s.
+    ///
+    /// When the guard is dropped, the hit objects are sorted by their start
+    /// time again so that modifying start times cannot break the order.
+    pub const fn hit_objects_mut(&mut self) -> HitObjectsGuard<'_> {
+        HitObjectsGuard {
+            hit_objects: &mut self.hit_objects,
+        }
+    }
+
+    /// Returns all [`HitObject`]s whose start time matches the given time,
+    /// e.g. all notes of a chord in mania.
+    ///
+    /// Start times within a tenth of a millisecond of `time` are considered
+    /// matching to account for floating point inaccuracies.
+    ///
+    /// Hit objects are expected to be sorted by their start time which is
+    /// the case for decoded beatmaps.
+    pub fn objects_at_time(&self, time: f64) -> &[HitObject] {
+        const TOLERANCE: f64 = 0.1;
+
+        let start = self
+            .hit_objects
+            .partition_point(|h| h.start_time < time - TOLERANCE);
+
+        let len = self.hit_objects[start..].partition_point(|h| h.start_time <= time + TOLERANCE);
+
+        &self.hit_objects[start..start + len]
+    }
+
+    /// Returns all [`HitObject`]s whose time span from start to end time
+    /// overlaps with the range `from..=to`.
+    ///
+    /// Hit objects are expected to be sorted by their start time which is
+    /// the case for decoded beatmaps. The objects starting within the range
+    /// are found through binary search. Sliders, spinners, and hold notes
+    /// that start before `from` may still overlap so only those that start
+    /// at most the longest duration of any earlier object before `from` are
+    /// checked.
+    ///
+    /// Determining that duration requires the curves of all sliders before
+    /// `from` which are calculated with the given [`CurveBuffers`] if they
+    /// have not yet been accessed.
+    pub fn hit_objects_in_range<'a>(
+        &'a mut self,
+        from: f64,
+        to: f64,
+        bufs: &'a mut CurveBuffers,
+    ) -> impl Iterator<Item = &'a HitObject> {
+        let start = self.hit_objects.partition_point(|h| h.start_time < from);
+        let end = start + self.hit_objects[start..].partition_point(|h| h.start_time <= to);
+
+        let max_duration = self.hit_objects[..start]
+            .iter_mut()
+            .map(|h| h.end_time_with_bufs(bufs) - h.start_time)
+            .fold(0.0, f64::max);
+
+        let lower =
+            self.hit_objects[..start].partition_point(|h| h.start_time < from - max_duration);
+        let (before, within) = self.hit_objects[lower..end].split_at_mut(start - lower);
+
+        before
+            .iter_mut()
+            .filter_map(move |h| (h.end_time_with_bufs(bufs) >= from).then_some(&*h))
+            .chain(within.iter())
+    }
+
+    /// The highest amount of [`HitObject`]s that start within any window of
+    /// one second.
+    ///
+    /// Hit objects are expected to be sorted by their start time which is
+    /// the case for decoded beatmaps.
+    pub fn peak_objects_per_second(&self) -> f64 {
+        const WINDOW: f64 = 1000.0;
+
+        let mut start = 0;
+        let mut peak = 0;
+
+        for (end, h) in self.hit_objects.iter().enumerate() {
+            while h.start_time - self.hit_objects[start].start_time >= WINDOW {
+                start += 1;
+            }
+
+            peak = peak.max(end + 1 - start);
+        }
+
+        peak as f64
+    }
+
+    /// Returns the end time of the [`HitObject`] at index `idx` or `None` if
+    /// the index is out of bounds.
+    ///
+    /// If the hit object is a slider whose curve has not yet been accessed,
+    /// it is calculated with the given [`CurveBuffers`] and cached in the
+    /// slider's path. Re-using the same buffers across calls avoids
+    /// re-allocating them and subsequent calls for that slider don't need to
+    /// calculate anything. The trade-off is that each cached curve keeps its
+    /// memory until [`SliderPath::clear_curve`] is called, e.g. after
+    /// mutating the slider.
+    ///
+    /// [`SliderPath::clear_curve`]: crate::section::hit_objects::SliderPath::clear_curve
+    pub fn end_time_of(&mut self, idx: usize, bufs: &mut CurveBuffers) -> Option<f64> {
+        self.hit_objects
+            .get_mut(idx)
+            .map(|h| h.end_time_with_bufs(bufs))
+    }
+
+    /// Sums up the base score values of all [`HitObject`]s as legacy score
+    /// (`ScoreV1`) awards them in osu!standard, ignoring combo and mod
+    /// multipliers.
+    ///
+    /// - Circles give 300.
+    /// - Sliders give 300 plus 30 for their head, each repeat, and their
+    ///   tail, and 10 for each tick.
+    /// - Spinners give 300 plus 100 for every full spin and 1100 for every
+    ///   bonus spin, assuming the maximum spin rate. The amount of required
+    ///   spins depends on the overall difficulty.
+    ///
+    /// Hold notes are counted like circles.
+    pub fn object_value_sum(&mut self) -> u32 {
+        const CIRCLE: u32 = 300;
+        const SLIDER_NESTED: u32 = 30;
+        const SLIDER_TICK: u32 = 10;
+        const SPINNER_SPIN: u32 = 100;
+        const SPINNER_BONUS_SPIN: u32 = 1100;
+        const MAX_ROTATIONS_PER_SEC: f64 = 477.0 / 60.0;
+
+        let min_rotations_per_sec = difficulty::od_to_spinner_rps(self.overall_difficulty);
+
+        let mut bufs = CurveBuffers::default();
+        let mut ticks = Vec::new();
+        let mut sum = 0;
+
+        for h in self.hit_objects.iter_mut() {
+            match h.kind {
+                HitObjectKind::Circle(_) | HitObjectKind::Hold(_) => sum += CIRCLE,
+                HitObjectKind::Slider(ref mut slider) => {
+                    let events = slider.osu_events(
+                        h.start_time,
+                        self.format_version,
+                        self.slider_tick_rate,
+                        &self.control_points,
+                        &mut bufs,
+                        &mut ticks,
+                    );
+
+                    for event in events {
+                        sum += match event.kind {
+                            SliderEventType::Head
+                            | SliderEventType::Repeat
+                            | SliderEventType::Tail => SLIDER_NESTED,
+                            SliderEventType::Tick => SLIDER_TICK,
+                            SliderEventType::LastTick => 0,
+                        };
+                    }
+
+                    sum += CIRCLE;
+                }
+                HitObjectKind::Spinner(ref spinner) => {
+                    let secs = spinner.duration / 1000.0;
+
+                    let total_half_spins = (secs * MAX_ROTATIONS_PER_SEC * 2.0) as u32;
+                    let required_half_spins = (secs * min_rotations_per_sec) as u32;
+                    let half_spins_before_bonus = required_half_spins + 3;
+
+                    for i in 0..=total_half_spins {
+                        if i > half_spins_before_bonus
+                            && (i - half_spins_before_bonus).is_multiple_of(2)
+                        {
+                            sum += SPINNER_BONUS_SPIN;
+                        } else if i > 1 && i.is_multiple_of(2) {
+                            sum += SPINNER_SPIN;
+                        }
+                    }
+
+                    sum += CIRCLE;
+                }
+            }
+        }
+
+        sum
+    }
+
+    /// Removes all storyboard events, i.e. sprites, animations, samples, and
+    /// background color changes.
+    ///
+    /// The background file and [`BreakPeriod`]s are kept.
+    pub fn strip_storyboard(&mut self) {
+        self.storyboard_files.clear();
+        self.background_colors.clear();
+    }
+
+    /// Removes all [`BreakPeriod`]s.
+    pub fn clear_breaks(&mut self) {
+        self.breaks.clear();
+    }
+
+    /// Resets the samples of all [`HitObject`]s, including slider node
+    /// samples, to a single normal sample.
+    ///
+    /// This removes all whistles, finishes, claps, and custom sample files.
+    pub fn strip_hit_sounds(&mut self) {
+        for h in self.hit_objects.iter_mut() {
+            h.samples = HitObject::default_samples();
+
+            if let HitObjectKind::Slider(ref mut slider) = h.kind {
+                for node_samples in slider.node_samples.iter_mut() {
+                    *node_samples = HitObject::default_samples();
+                }
+            }
+        }
+    }
+
+    /// Returns all distinct [`SampleBank`]s that are referenced by the
+    /// samples of [`HitObject`]s, including slider node samples, and by
+    /// [`SamplePoint`]s.
+    ///
+    /// [`SampleBank::None`] is not included.
+    pub fn sample_banks_used(&self) -> HashSet<SampleBank> {
+        let hit_object_samples = self.hit_objects.iter().flat_map(|h| {
+            let node_samples = match h.kind {
+                HitObjectKind::Slider(ref slider) => slider.node_samples.as_slice(),
+                _ => &[],
+            };
+
+            h.samples.iter().chain(node_samples.iter().flatten())
+        });
+
+        let point_banks = self
+            .control_points
+            .sample_points
+            .iter()
+            .map(|point| point.sample_bank);
+
+        hit_object_samples
+            .map(|sample| sample.bank)
+            .chain(point_banks)
+            .filter(|bank| *bank != SampleBank::None)
+            .collect()
+    }
+
+    /// Calculates the stack height of all circles and sliders like
+    /// osu!standard does and stores it in their `stack_height` field.
+    ///
+    /// Objects within the stack window, determined by the approach rate and
+    /// [`Beatmap::stack_leniency`], that are close to each other are
+    /// stacked. Beatmaps with a format version below 6 use the older
+    /// stacking algorithm.
+    ///
+    /// The stacked position of an object is its position offset by
+    /// `stack_height * scale * -6.4` on both axes where `scale` is
+    /// `(1.0 - 0.7 * (circle_size - 5.0) / 5.0) / 2.0`.
+    ///
+    /// Stack heights are reset to zero if the [`GameMode`] is not
+    /// [`GameMode::Osu`].
+    pub fn apply_stacking(&mut self) {
+        let heights = if self.mode == GameMode::Osu {
+            let stack_threshold = self.ar_to_preempt_ms() * f64::from(self.stack_leniency);
+
+            stacking::stack_heights(&mut self.hit_objects, self.format_version, stack_threshold)
+        } else {
+            vec![0; self.hit_objects.len()]
+        };
+
+        for (h, height) in self.hit_objects.iter_mut().zip(heights) {
+            match h.kind {
+                HitObjectKind::Circle(ref mut circle) => circle.stack_height = height,
+                HitObjectKind::Slider(ref mut slider) => slider.stack_height = height,
+                HitObjectKind::Spinner(_) | HitObjectKind::Hold(_) => {}
+            }
+        }
+    }
+
+    /// Calculates the maximum achievable combo depending on the
+    /// [`GameMode`].
+    ///
+    /// - osu!standard: Circles and spinners count once, sliders count their
+    ///   head, ticks, repeats, and tail.
+    /// - osu!taiko: Only circles count; drumrolls and swells give no combo.
+    /// - osu!catch: Fruits count once, sliders count their head, droplets,
+    ///   repeats, and tail. Banana showers give no combo.
+    /// - osu!mania: Notes count once, hold notes count their head and tail.
+    pub fn max_combo(&mut self) -> u32 {
+        let mut bufs = CurveBuffers::default();
+        let mut ticks = Vec::new();
+        let mut combo = 0;
+
+        for h in self.hit_objects.iter_mut() {
+            combo += match (self.mode, &mut h.kind) {
+                (GameMode::Osu | GameMode::Catch, HitObjectKind::Slider(slider)) => {
+                    let events = slider.osu_events(
+                        h.start_time,
+                        self.format_version,
+                        self.slider_tick_rate,
+                        &self.control_points,
+                        &mut bufs,
+                        &mut ticks,
+                    );
+
+                    // The legacy last tick acts as tail in osu!standard
+                    // whereas osu!catch only considers the actual tail.
+                    events
+                        .filter(|event| event.kind != SliderEventType::LastTick)
+                        .count() as u32
+                }
+                (GameMode::Osu, _) | (_, HitObjectKind::Circle(_)) => 1,
+                (GameMode::Mania, HitObjectKind::Hold(_)) => 2,
+                _ => 0,
+            };
+        }
+
+        combo
+    }
+
+    /// Returns the combo index of each [`HitObject`], incorporating the
+    /// color skips of new combos.
+    ///
+    /// The combo color of a hit object is the custom combo color at
+    /// `index % custom_combo_colors.len()`.
+    ///
+    /// Spinners don't participate in combos so they keep the index of the
+    /// previous hit object.
+    pub fn combo_color_skips(&self) -> Vec<usize> {
+        let mut combo_index = 0;
+
+        self.hit_objects
+            .iter()
+            .map(|h| {
+                let combo_offset = match h.kind {
+                    HitObjectKind::Circle(ref h) => h.new_combo.then_some(h.combo_offset),
+                    HitObjectKind::Slider(ref h) => h.new_combo.then_some(h.combo_offset),
+                    HitObjectKind::Spinner(_) | HitObjectKind::Hold(_) => None,
+                };
+
+                if let Some(combo_offset) = combo_offset {
+                    combo_index += combo_offset as usize + 1;
+                }
+
+                combo_index
+            })
+            .collect()
+    }
+
+    /// Decompose the [`Beatmap`] into its sections.
+    ///
+    /// Since [`TimingPoints`] also contains the `[General]` section, its
+    /// general fields are the same as those of [`General`].
+    ///
+    /// Note that the format version is not part of any section.
+    #[allow(clippy::too_many_lines, clippy::type_complexity)]
+    pub fn into_sections(
+        self,
+    ) -> (
+        General,
+        Editor,
+        Metadata,
+        Difficulty,
+        Events,
+        TimingPoints,
+        Colors,
+        Vec<HitObject>,
+    ) {
+        let Self {
+            format_version: _,
+            audio_file,
+            audio_lead_in,
+            preview_time,
+            default_sample_bank,
+            default_sample_volume,
+            stack_leniency,
+            mode,
+            letterbox_in_breaks,
+            special_style,
+            widescreen_storyboard,
+            epilepsy_warning,
+            samples_match_playback_rate,
+            countdown,
+            countdown_offset,
+            bookmarks,
+            distance_spacing,
+            beat_divisor,
+            grid_size,
+            timeline_zoom,
+            title,
+            title_unicode,
+            artist,
+            artist_unicode,
+            creator,
+            version,
+            source,
+            tags,
+            beatmap_id,
+            beatmap_set_id,
+            hp_drain_rate,
+            circle_size,
+            overall_difficulty,
+            approach_rate,
+            slider_multiplier,
+            slider_tick_rate,
+            background_file,
+            breaks,
+            background_colors,
+            storyboard_files,
+            control_points,
+            custom_combo_colors,
+            custom_colors,
+            slider_track_override,
+            slider_border,
+            hit_objects,
+        } = self;
+
+        let general = General {
+            audio_file,
+            audio_lead_in,
+            preview_time,
+            default_sample_bank,
+            default_sample_volume,
+            stack_leniency,
+            mode,
+            letterbox_in_breaks,
+            special_style,
+            widescreen_storyboard,
+            epilepsy_warning,
+            samples_match_playback_rate,
+            countdown,
+            countdown_offset,
+        };
+
+        let editor = Editor {
+            bookmarks,
+            distance_spacing,
+            beat_divisor,
+            grid_size,
+            timeline_zoom,
+        };
+
+        let metadata = Metadata {
+            title,
+            title_unicode,
+            artist,
+            artist_unicode,
+            creator,
+            version,
+            source,
+            tags,
+            beatmap_id,
+            beatmap_set_id,
+        };
+
+        let difficulty = Difficulty {
+            hp_drain_rate,
+            circle_size,
+            overall_difficulty,
+            approach_rate,
+            slider_multiplier,
+            slider_tick_rate,
+        };
+
+        let events = Events {
+            background_file,
+            breaks,
+            background_colors,
+            storyboard_files,
+        };
+
+        let timing_points = TimingPoints {
+            audio_file: general.audio_file.clone(),
+            audio_lead_in: general.audio_lead_in,
+            preview_time: general.preview_time,
+            default_sample_bank: general.default_sample_bank,
+            default_sample_volume: general.default_sample_volume,
+            stack_leniency: general.stack_leniency,
+            mode: general.mode,
+            letterbox_in_breaks: general.letterbox_in_breaks,
+            special_style: general.special_style,
+            widescreen_storyboard: general.widescreen_storyboard,
+            epilepsy_warning: general.epilepsy_warning,
+            samples_match_playback_rate: general.samples_match_playback_rate,
+            countdown: general.countdown,
+            countdown_offset: general.countdown_offset,
+            control_points,
+        };
+
+        let colors = Colors {
+            custom_combo_colors,
+            custom_colors,
+            slider_track_override,
+            slider_border,
+        };
+
+        (
+            general,
+            editor,
+            metadata,
+            difficulty,
+            events,
+            timing_points,
+            colors,
+            hit_objects,
+        )
+    }
+
+    /// Reassemble a [`Beatmap`] from its sections.
+    ///
+    /// The `[General]` fields are taken from [`General`] so only the control
+    /// points of [`TimingPoints`] are used. The format version will be
+    /// [`LATEST_FORMAT_VERSION`].
+    #[allow(clippy::type_complexity)]
+    pub fn from_sections(
+        (general, editor, metadata, difficulty, events, timing_points, colors, hit_objects): (
+            General,
+            Editor,
+            Metadata,
+            Difficulty,
+            Events,
+            TimingPoints,
+            Colors,
+            Vec<HitObject>,
+        ),
+    ) -> Self {
+        Self {
+            format_version: LATEST_FORMAT_VERSION,
+            audio_file: general.audio_file,
+            audio_lead_in: general.audio_lead_in,
+            preview_time: general.preview_time,
+            default_sample_bank: general.default_sample_bank,
+            default_sample_volume: general.default_sample_volume,
+            stack_leniency: general.stack_leniency,
+            mode: general.mode,
+            letterbox_in_breaks: general.letterbox_in_breaks,
+            special_style: general.special_style,
+            widescreen_storyboard: general.widescreen_storyboard,
+            epilepsy_warning: general.epilepsy_warning,
+            samples_match_playback_rate: general.samples_match_playback_rate,
+            countdown: general.countdown,
+            countdown_offset: general.countdown_offset,
+            bookmarks: editor.bookmarks,
+            distance_spacing: editor.distance_spacing,
+            beat_divisor: editor.beat_divisor,
+            grid_size: editor.grid_size,
+            timeline_zoom: editor.timeline_zoom,
+            title: metadata.title,
+            title_unicode: metadata.title_unicode,
+            artist: metadata.artist,
+            artist_unicode: metadata.artist_unicode,
+            creator: metadata.creator,
+            version: metadata.version,
+            source: metadata.source,
+            tags: metadata.tags,
+            beatmap_id: metadata.beatmap_id,
+            beatmap_set_id: metadata.beatmap_set_id,
+            hp_drain_rate: difficulty.hp_drain_rate,
+            circle_size: difficulty.circle_size,
+            overall_difficulty: difficulty.overall_difficulty,
+            approach_rate: difficulty.approach_rate,
+            slider_multiplier: difficulty.slider_multiplier,
+            slider_tick_rate: difficulty.slider_tick_rate,
+            background_file: events.background_file,
+            breaks: events.breaks,
+            background_colors: events.background_colors,
+            storyboard_files: events.storyboard_files,
+            control_points: timing_points.control_points,
+            custom_combo_colors: colors.custom_combo_colors,
+            custom_colors: colors.custom_colors,
+            slider_track_override: colors.slider_track_override,
+            slider_border: colors.slider_border,
+            hit_objects,
+        }
+    }
+
+    /// Whether `self` and `other` are equal while allowing floating point
+    /// values to differ by at most `epsilon`.
+    ///
+    /// This includes floats of control points and hit objects. All other
+    /// fields are compared exactly.
+    ///
+    /// As opposed to [`PartialEq`], NaN values are considered equal.
+    pub fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        let Self {
+            format_version,
+            audio_file,
+            audio_lead_in,
+            preview_time,
+            default_sample_bank,
+            default_sample_volume,
+            stack_leniency,
+            mode,
+            letterbox_in_breaks,
+            special_style,
+            widescreen_storyboard,
+            epilepsy_warning,
+            samples_match_playback_rate,
+            countdown,
+            countdown_offset,
+            bookmarks,
+            distance_spacing,
+            beat_divisor,
+            grid_size,
+            timeline_zoom,
+            title,
+            title_unicode,
+            artist,
+            artist_unicode,
+            creator,
+            version,
+            source,
+            tags,
+            beatmap_id,
+            beatmap_set_id,
+            hp_drain_rate,
+            circle_size,
+            overall_difficulty,
+            approach_rate,
+            slider_multiplier,
+            slider_tick_rate,
+            background_file,
+            breaks,
+            background_colors,
+            storyboard_files,
+            control_points,
+            custom_combo_colors,
+            custom_colors,
+            slider_track_override,
+            slider_border,
+            hit_objects,
+        } = self;
+
+        *format_version == other.format_version
+            && *audio_file == other.audio_file
+            && audio_lead_in.approx_eq(&other.audio_lead_in, epsilon)
+            && *preview_time == other.preview_time
+            && *default_sample_bank == other.default_sample_bank
+            && *default_sample_volume == other.default_sample_volume
+            && stack_leniency.approx_eq(&other.stack_leniency, epsilon)
+            && *mode == other.mode
+            && *letterbox_in_breaks == other.letterbox_in_breaks
+            && *special_style == other.special_style
+            && *widescreen_storyboard == other.widescreen_storyboard
+            && *epilepsy_warning == other.epilepsy_warning
+            && *samples_match_playback_rate == other.samples_match_playback_rate
+            && *countdown == other.countdown
+            && *countdown_offset == other.countdown_offset
+            && *bookmarks == other.bookmarks
+            && distance_spacing.approx_eq(&other.distance_spacing, epsilon)
+            && *beat_divisor == other.beat_divisor
+            && *grid_size == other.grid_size
+            && timeline_zoom.approx_eq(&other.timeline_zoom, epsilon)
+            && *title == other.title
+            && *title_unicode == other.title_unicode
+            && *artist == other.artist
+            && *artist_unicode == other.artist_unicode
+            && *creator == other.creator
+            && *version == other.version
+            && *source == other.source
+            && *tags == other.tags
+            && *beatmap_id == other.beatmap_id
+            && *beatmap_set_id == other.beatmap_set_id
+            && hp_drain_rate.approx_eq(&other.hp_drain_rate, epsilon)
+            && circle_size.approx_eq(&other.circle_size, epsilon)
+            && overall_difficulty.approx_eq(&other.overall_difficulty, epsilon)
+            && approach_rate.approx_eq(&other.approach_rate, epsilon)
+            && slider_multiplier.approx_eq(&other.slider_multiplier, epsilon)
+            && slider_tick_rate.approx_eq(&other.slider_tick_rate, epsilon)
+            && *background_file == other.background_file
+            && breaks.approx_eq(&other.breaks, epsilon)
+            && background_colors.approx_eq(&other.background_colors, epsilon)
+            && *storyboard_files == other.storyboard_files
+            && control_points.approx_eq(&other.control_points, epsilon)
+            && *custom_combo_colors == other.custom_combo_colors
+            && *custom_colors == other.custom_colors
+            && *slider_track_override == other.slider_track_override
+            && *slider_border == other.slider_border
+            && hit_objects.approx_eq(&other.hit_objects, epsilon)
+    }
+
+    /// Sets the slider velocity multiplier starting at the given time by
+    /// adding or updating a [`DifficultyPoint`].
+    ///
+    /// The velocity of all affected sliders is recalculated.
+    pub fn set_slider_velocity(&mut self, time: f64, multiplier: f64) {
+        let generate_ticks = self
+            .control_points
+            .difficulty_point_at(time)
+            .map_or(DifficultyPoint::DEFAULT_GENERATE_TICKS, |point| {
+                point.generate_ticks
+            });
+
+        let point = DifficultyPoint {
+            generate_ticks,
+            ..DifficultyPoint::new(time, -100.0 / multiplier, multiplier)
+        };
+
+        self.control_points.add(point);
+
+        for h in self.hit_objects.iter_mut() {
+            if h.start_time < time {
+                continue;
+            }
+
+            if let HitObjectKind::Slider(ref mut slider) = h.kind {
+                slider.velocity = slider_velocity_at(
+                    h.start_time,
+                    &self.control_points,
+                    self.slider_multiplier,
+                    self.mode,
+                );
+            }
+        }
+    }
+
+    /// Finds the [`DifficultyPoint`] that is active at the given time.
+    pub fn difficulty_point_at(&self, time: f64) -> Option<&DifficultyPoint> {
+        self.control_points.difficulty_point_at(time)
+    }
+
+    /// The slider velocity multiplier of the [`DifficultyPoint`] that is
+    /// active at the given time, e.g. `1.5` for what editors display as
+    /// "1.5x".
+    ///
+    /// In contrast to the velocity of a [`HitObjectSlider`], this does not
+    /// take the beat length or the beatmap's slider multiplier into account.
+    ///
+    /// [`HitObjectSlider`]: crate::section::hit_objects::HitObjectSlider
+    pub fn sv_multiplier_at(&self, time: f64) -> f64 {
+        self.difficulty_point_at(time)
+            .map_or(DifficultyPoint::DEFAULT_SLIDER_VELOCITY, |point| {
+                point.slider_velocity
+            })
+    }
+
+    /// Finds the [`EffectPoint`] that is active at the given time.
+    pub fn effect_point_at(&self, time: f64) -> Option<&EffectPoint> {
+        self.control_points.effect_point_at(time)
+    }
+
+    /// Finds the [`SamplePoint`] that is active at the given time.
+    pub fn sample_point_at(&self, time: f64) -> Option<&SamplePoint> {
+        self.control_points.sample_point_at(time)
+    }
+
+    /// Finds the [`TimingPoint`] that is active at the given time.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use rosu_map::Beatmap;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let path = "./resources/Soleily - Renatus (Gamu) [Insane].osu";
+    /// let map = Beatmap::from_path(path)?;
+    ///
+    /// let beat_len = map.timing_point_at(20_000.0).map(|point| point.beat_len);
+    /// assert_eq!(beat_len, Some(329.67032967033));
+    /// # Ok(()) }
+    /// ```
+    pub fn timing_point_at(&self, time: f64) -> Option<&TimingPoint> {
+        self.control_points.timing_point_at(time)
+    }
+}
+
+/// Split off all points after `time` and prepend a copy of the point that is
+/// active at `time`, moved to `time`.
+fn split_off_active<P: Clone>(
+    points: &mut Vec<P>,
+    time: f64,
+    time_mut: fn(&mut P) -> &mut f64,
+) -> Vec<P> {
+    let idx = points
+        .iter_mut()
+        .position(|point| *time_mut(point) > time)
+        .unwrap_or(points.len());
+    let mut after = points.split_off(idx);
+
+    if let Some(active) = points.last() {
+        let mut active = active.clone();
+        *time_mut(&mut active) = time;
+        after.insert(0, active);
+    }
+
+    after
+}
+
+/// Guard to mutate the [`HitObject`]s of a [`Beatmap`] that sorts them by
+/// their start time when dropped.
+///
+/// Created through [`Beatmap::hit_objects_mut`].
+pub struct HitObjectsGuard<'a> {
+    hit_objects: &'a mut Vec<HitObject>,
+}
+
+impl Deref for HitObjectsGuard<'_> {
+    type Target = Vec<HitObject>;
+
+    fn deref(&self) -> &Self::Target {
+        self.hit_objects
+    }
+}
+
+impl DerefMut for HitObjectsGuard<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.hit_objects
+    }
+}
+
+impl Drop for HitObjectsGuard<'_> {
+    fn drop(&mut self) {
+        self.hit_objects
+            .sort_by(|a, b| a.start_time.total_cmp(&b.start_time));
+    }
 }
 
 impl FromStr for Beatmap {
@@ -195,9 +1548,13 @@ impl Default for Beatmap {
             slider_tick_rate: hit_objects.slider_tick_rate,
             background_file: hit_objects.background_file,
             breaks: hit_objects.breaks,
+            background_colors: hit_objects.background_colors,
+            storyboard_files: hit_objects.storyboard_files,
             control_points: hit_objects.control_points,
             custom_combo_colors: colors.custom_combo_colors,
             custom_colors: colors.custom_colors,
+            slider_track_override: colors.slider_track_override,
+            slider_border: colors.slider_border,
             hit_objects: hit_objects.hit_objects,
         }
     }
@@ -225,6 +1582,72 @@ pub struct BeatmapState {
     pub metadata: MetadataState,
     pub colors: ColorsState,
     pub hit_objects: HitObjectsState,
+    /// Variables of the `[Variables]` section mapping their name, including
+    /// the leading `$`, to their value.
+    pub variables: HashMap<String, String>,
+}
+
+impl BeatmapState {
+    /// The maximum length of a line after substituting variables.
+    ///
+    /// Prevents variables such as `$a=$a$a` from blowing up the line.
+    const MAX_SUBSTITUTED_LEN: usize = 4096;
+
+    /// Replaces all occurrences of known variables in the given line.
+    ///
+    /// If the substituted line would exceed [`Self::MAX_SUBSTITUTED_LEN`],
+    /// the original line is returned instead.
+    fn substitute_variables<'a>(&self, line: &'a str) -> Cow<'a, str> {
+        if self.variables.is_empty() || !line.contains('$') {
+            return Cow::Borrowed(line);
+        }
+
+        // Longer names come first so that `$ab` is not replaced as `$a`
+        let mut variables: Vec<_> = self.variables.iter().collect();
+        variables.sort_unstable_by(|(a, _), (b, _)| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+
+        let original = line;
+        let mut line = Cow::Borrowed(line);
+
+        // Values may contain variables themselves so keep substituting until
+        // nothing changes anymore. The amount of passes is bounded to
+        // prevent self-referencing variables from looping forever.
+        for _ in 0..=self.variables.len() {
+            if !line.contains('$') {
+                break;
+            }
+
+            let mut changed = false;
+
+            for &(name, value) in variables.iter() {
+                if name == value {
+                    continue;
+                }
+
+                let count = line.matches(name.as_str()).count();
+
+                if count == 0 {
+                    continue;
+                }
+
+                let new_len = (line.len() - count * name.len())
+                    .saturating_add(count.saturating_mul(value.len()));
+
+                if new_len > Self::MAX_SUBSTITUTED_LEN {
+                    return Cow::Borrowed(original);
+                }
+
+                line = Cow::Owned(line.replace(name.as_str(), value));
+                changed = true;
+            }
+
+            if !changed {
+                break;
+            }
+        }
+
+        line
+    }
 }
 
 impl DecodeState for BeatmapState {
@@ -235,6 +1658,14 @@ impl DecodeState for BeatmapState {
             metadata: MetadataState::create(version),
             colors: ColorsState::create(version),
             hit_objects: HitObjectsState::create(version),
+            variables: HashMap::new(),
+        }
+    }
+
+    fn create_with_options(version: i32, options: &DecodeOptions) -> Self {
+        Self {
+            hit_objects: HitObjectsState::create_with_options(version, options),
+            ..Self::create(version)
         }
     }
 }
@@ -286,9 +1717,13 @@ impl From<BeatmapState> for Beatmap {
             slider_tick_rate: hit_objects.slider_tick_rate,
             background_file: hit_objects.background_file,
             breaks: hit_objects.breaks,
+            background_colors: hit_objects.background_colors,
+            storyboard_files: hit_objects.storyboard_files,
             control_points: hit_objects.control_points,
             custom_combo_colors: colors.custom_combo_colors,
             custom_colors: colors.custom_colors,
+            slider_track_override: colors.slider_track_override,
+            slider_border: colors.slider_border,
             hit_objects: hit_objects.hit_objects,
         }
     }
@@ -317,7 +1752,10 @@ impl DecodeBeatmap for Beatmap {
     }
 
     fn parse_events(state: &mut Self::State, line: &str) -> Result<(), Self::Error> {
-        HitObjects::parse_events(&mut state.hit_objects, line).map_err(ParseBeatmapError::HitOjects)
+        let line = state.substitute_variables(line);
+
+        HitObjects::parse_events(&mut state.hit_objects, &line)
+            .map_err(ParseBeatmapError::HitOjects)
     }
 
     fn parse_timing_points(state: &mut Self::State, line: &str) -> Result<(), Self::Error> {
@@ -334,7 +1772,19 @@ impl DecodeBeatmap for Beatmap {
             .map_err(ParseBeatmapError::HitOjects)
     }
 
-    fn parse_variables(_: &mut Self::State, _: &str) -> Result<(), Self::Error> {
+    fn parse_variables(state: &mut Self::State, line: &str) -> Result<(), Self::Error> {
+        let Some((name, value)) = line.split_once('=') else {
+            return Ok(());
+        };
+
+        let name = name.trim();
+
+        if !name.is_empty() {
+            state
+                .variables
+                .insert(name.to_owned(), value.trim().to_owned());
+        }
+
         Ok(())
     }
 
@@ -72,6 +72,39 @@ pub fn from_str<D: DecodeBeatmap>(s: &str) -> Result<D, io::Error> {
     D::decode(Cursor::new(s))
 }
 
+/// Parse a type that implements [`DecodeBeatmap`] by providing a reader of
+/// the content of a `.osu` file.
+///
+/// # Example
+///
+/// ```rust
+/// use std::io::Cursor;
+/// use rosu_map::section::editor::Editor;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let reader = Cursor::new("[Editor]
+/// BeatDivisor: 4");
+///
+/// let editor: Editor = rosu_map::from_reader(reader)?;
+/// assert_eq!(editor.beat_divisor, 4);
+/// # Ok(()) }
+/// ```
+pub fn from_reader<D: DecodeBeatmap, R: BufRead>(reader: R) -> Result<D, io::Error> {
+    D::decode(reader)
+}
+
+/// Options to adjust the behavior of [`DecodeBeatmap::decode_with_options`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DecodeOptions {
+    /// Whether hit objects that are identical to their preceding hit object
+    /// after sorting should be dropped.
+    ///
+    /// Combo information is not considered when comparing hit objects.
+    ///
+    /// Defaults to `false`.
+    pub dedup_hit_objects: bool,
+}
+
 /// Intermediate state while parsing via [`DecodeBeatmap`].
 pub trait DecodeState: Sized {
     /// Given the format version, create an instance.
@@ -79,6 +112,15 @@ pub trait DecodeState: Sized {
     /// If the version is not of interest, this is basically
     /// `Default::default()`.
     fn create(version: i32) -> Self;
+
+    /// Given the format version and [`DecodeOptions`], create an instance.
+    ///
+    /// Only needs to be implemented if the options are of interest. Otherwise,
+    /// this defaults to [`DecodeState::create`].
+    #[allow(unused_variables)]
+    fn create_with_options(version: i32, options: &DecodeOptions) -> Self {
+        Self::create(version)
+    }
 }
 
 /// Trait to handle reading and parsing content of `.osu` files.
@@ -259,42 +301,59 @@ pub trait DecodeBeatmap: Sized {
     ///
     /// This method should not be implemented manually.
     fn decode<R: BufRead>(src: R) -> Result<Self, io::Error> {
-        let mut reader = Decoder::new(src)?;
-
-        let (version, use_curr_line) = parse_version(&mut reader)?;
-        let mut state =
-            Self::State::create(version.unwrap_or(format_version::LATEST_FORMAT_VERSION));
-
-        let Some(mut section) = parse_first_section(&mut reader, use_curr_line)? else {
-            return Ok(state.into());
-        };
+        Self::decode_with_options(src, &DecodeOptions::default())
+    }
 
-        loop {
-            let parse_fn = match section {
-                Section::General => Self::parse_general,
-                Section::Editor => Self::parse_editor,
-                Section::Metadata => Self::parse_metadata,
-                Section::Difficulty => Self::parse_difficulty,
-                Section::Events => Self::parse_events,
-                Section::TimingPoints => Self::parse_timing_points,
-                Section::Colors => Self::parse_colors,
-                Section::HitObjects => Self::parse_hit_objects,
-                Section::Variables => Self::parse_variables,
-                Section::CatchTheBeat => Self::parse_catch_the_beat,
-                Section::Mania => Self::parse_mania,
-            };
-
-            match parse_section::<_, Self>(&mut reader, &mut state, parse_fn) {
-                Ok(SectionFlow::Continue(next)) => section = next,
-                Ok(SectionFlow::Break(())) => break,
-                Err(err) => return Err(err),
-            }
-        }
+    /// Same as [`DecodeBeatmap::decode`] but with the given [`DecodeOptions`].
+    ///
+    /// This method should not be implemented manually.
+    fn decode_with_options<R: BufRead>(src: R, options: &DecodeOptions) -> Result<Self, io::Error> {
+        decode_inner(src, options, |_, _, _| {})
+    }
 
-        Ok(state.into())
+    /// Same as [`DecodeBeatmap::decode`] but `hook` is called for each line
+    /// that failed to be parsed.
+    ///
+    /// The hook receives the line's section, the line itself, and the error.
+    /// As opposed to logging through the `tracing` feature, the hook is
+    /// called regardless of enabled features.
+    ///
+    /// This method should not be implemented manually.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rosu_map::DecodeBeatmap;
+    /// use rosu_map::section::{difficulty::Difficulty, Section};
+    ///
+    /// let content: &str = "[Difficulty]
+    /// CircleSize: 4
+    /// ApproachRate: nine";
+    ///
+    /// let mut errors = Vec::new();
+    ///
+    /// let difficulty = Difficulty::decode_with_hook(content.as_bytes(), |section, line, _| {
+    ///     errors.push((*section, line.to_owned()));
+    /// })
+    /// .unwrap();
+    ///
+    /// assert_eq!(difficulty.circle_size, 4.0);
+    /// assert_eq!(errors, [(Section::Difficulty, "ApproachRate: nine".to_owned())]);
+    /// ```
+    fn decode_with_hook<R, F>(src: R, hook: F) -> Result<Self, io::Error>
+    where
+        R: BufRead,
+        F: FnMut(&Section, &str, &dyn Error),
+    {
+        decode_inner(src, &DecodeOptions::default(), hook)
     }
 
     /// Whether a line should *not* be forwarded to the parsing methods.
+    ///
+    /// By default, only empty lines and comments, i.e. lines starting with
+    /// `//` after leading whitespace, are skipped. Lines with leading spaces
+    /// or underscores, such as indented storyboard commands, are forwarded
+    /// as is so each section decides how to handle them.
     fn should_skip_line(line: &str) -> bool {
         line.is_empty() || line.trim_start().starts_with("//")
     }
@@ -344,6 +403,47 @@ pub trait DecodeBeatmap: Sized {
     fn parse_mania(state: &mut Self::State, line: &str) -> Result<(), Self::Error>;
 }
 
+fn decode_inner<D, R, F>(src: R, options: &DecodeOptions, mut hook: F) -> Result<D, io::Error>
+where
+    D: DecodeBeatmap,
+    R: BufRead,
+    F: FnMut(&Section, &str, &dyn Error),
+{
+    let mut reader = Decoder::new(src)?;
+
+    let (version, use_curr_line) = parse_version(&mut reader)?;
+    let version = version.unwrap_or(format_version::LATEST_FORMAT_VERSION);
+    let mut state = D::State::create_with_options(version, options);
+
+    let Some(mut section) = parse_first_section(&mut reader, use_curr_line)? else {
+        return Ok(state.into());
+    };
+
+    loop {
+        let parse_fn = match section {
+            Section::General => D::parse_general,
+            Section::Editor => D::parse_editor,
+            Section::Metadata => D::parse_metadata,
+            Section::Difficulty => D::parse_difficulty,
+            Section::Events => D::parse_events,
+            Section::TimingPoints => D::parse_timing_points,
+            Section::Colors => D::parse_colors,
+            Section::HitObjects => D::parse_hit_objects,
+            Section::Variables => D::parse_variables,
+            Section::CatchTheBeat => D::parse_catch_the_beat,
+            Section::Mania => D::parse_mania,
+        };
+
+        match parse_section::<_, D, _>(&mut reader, &mut state, section, parse_fn, &mut hook) {
+            Ok(SectionFlow::Continue(next)) => section = next,
+            Ok(SectionFlow::Break(())) => break,
+            Err(err) => return Err(err),
+        }
+    }
+
+    Ok(state.into())
+}
+
 struct UseCurrentLine(bool);
 
 fn parse_version<R>(reader: &mut Decoder<R>) -> Result<(Option<i32>, UseCurrentLine), io::Error>
@@ -400,14 +500,17 @@ fn parse_first_section<R: BufRead>(
 
 type SectionFlow = ControlFlow<(), Section>;
 
-fn parse_section<R, D>(
+fn parse_section<R, D, F>(
     reader: &mut Decoder<R>,
     state: &mut D::State,
+    section: Section,
     f: fn(&mut D::State, &str) -> Result<(), D::Error>,
+    hook: &mut F,
 ) -> Result<SectionFlow, io::Error>
 where
     R: BufRead,
     D: DecodeBeatmap,
+    F: FnMut(&Section, &str, &dyn Error),
 {
     loop {
         match reader.read_line() {
@@ -420,14 +523,14 @@ where
                     return Ok(SectionFlow::Continue(next));
                 }
 
-                // Only used when `tracing` feature is enabled
-                #[allow(unused)]
-                let res = f(state, line);
+                if let Err(err) = f(state, line) {
+                    #[cfg(feature = "tracing")]
+                    {
+                        tracing::error!("Failed to process line {line:?}: {err}");
+                        log_error_cause(&err);
+                    }
 
-                #[cfg(feature = "tracing")]
-                if let Err(err) = res {
-                    tracing::error!("Failed to process line {line:?}: {err}");
-                    log_error_cause(&err);
+                    hook(&section, line, &err);
                 }
             }
             Ok(None) => return Ok(SectionFlow::Break(())),
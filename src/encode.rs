@@ -1,4 +1,5 @@
 use std::{
+    fmt::{Display, Formatter, Result as FmtResult},
     fs::File,
     io::{BufWriter, Error as IoError, ErrorKind, Result as IoResult, Write},
     path::Path,
@@ -7,6 +8,8 @@ use std::{
 
 use crate::{
     beatmap::Beatmap,
+    format_version::FormatVersionHeader,
+    reader::Encoding,
     section::{
         difficulty::DifficultyKey,
         editor::EditorKey,
@@ -22,9 +25,63 @@ use crate::{
             ControlPoints, DifficultyPoint, EffectFlags, EffectPoint, SamplePoint, TimingPoint,
         },
     },
-    util::Pos,
+    util::{Pos, StrExt},
 };
 
+/// Options to adjust the behavior of [`Beatmap::encode_with_options`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EncodeOptions {
+    /// How the type of events in the `[Events]` section is written.
+    ///
+    /// Defaults to [`EventTypeStyle::Numeric`].
+    pub event_type_style: EventTypeStyle,
+    /// The text encoding of the output.
+    ///
+    /// Defaults to [`Encoding::Utf8`].
+    pub encoding: Encoding,
+    /// Whether the output starts with the byte order mark of the
+    /// [`Encoding`].
+    ///
+    /// Defaults to `false`.
+    pub write_bom: bool,
+    /// Whether encoding fails with an [`ErrorKind::InvalidInput`] error if
+    /// a difficulty value, a control point, or a hit object contains a NaN
+    /// or infinite value. Otherwise, such values are written as they are
+    /// which osu! cannot parse.
+    ///
+    /// Defaults to `false`.
+    pub reject_non_finite: bool,
+    /// Whether the `ApproachRate` line of the `[Difficulty]` section is
+    /// omitted if the approach rate equals the overall difficulty.
+    ///
+    /// Old beatmaps that don't specify an approach rate use the overall
+    /// difficulty instead so this keeps their `[Difficulty]` section as is.
+    ///
+    /// Defaults to `false`.
+    pub omit_default_approach_rate: bool,
+}
+
+/// How an [`EventType`] is written when encoding.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum EventTypeStyle {
+    /// The numeric value e.g. `2` for [`EventType::Break`].
+    #[default]
+    Numeric,
+    /// The name e.g. `Break` for [`EventType::Break`].
+    Name,
+}
+
+struct EventTypeDisplay(EventType, EventTypeStyle);
+
+impl Display for EventTypeDisplay {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self.1 {
+            EventTypeStyle::Numeric => write!(f, "{}", self.0 as i32),
+            EventTypeStyle::Name => f.write_str(self.0.to_str()),
+        }
+    }
+}
+
 impl Beatmap {
     /// Encode a [`Beatmap`] into content of a `.osu` file and store it at the
     /// given path.
@@ -64,11 +121,15 @@ impl Beatmap {
         let mut writer = Vec::with_capacity(4096);
         self.encode(&mut writer)?;
 
-        String::from_utf8(writer).map_err(|e| IoError::new(ErrorKind::Other, e))
+        String::from_utf8(writer).map_err(IoError::other)
     }
 
     /// Encode a [`Beatmap`] into content of a `.osu` file.
     ///
+    /// The `[Events]` section is written in a fixed order: the background,
+    /// breaks sorted by start time, background color changes sorted by time,
+    /// and lastly storyboard files in their original order.
+    ///
     /// # Example
     ///
     /// In case of writing directly to a file, it is recommended to pass the
@@ -108,8 +169,127 @@ impl Beatmap {
     /// ```
     ///
     /// [`encode_to_path`]: Beatmap::encode_to_path
-    pub fn encode<W: Write>(&mut self, mut writer: W) -> IoResult<()> {
-        writeln!(writer, "osu file format v{}", self.format_version)?;
+    pub fn encode<W: Write>(&mut self, writer: W) -> IoResult<()> {
+        self.encode_with_options(writer, &EncodeOptions::default())
+    }
+
+    /// Encode a [`Beatmap`] into content of a `.osu` file while adjusting
+    /// the output through [`EncodeOptions`].
+    ///
+    /// See [`Beatmap::encode`].
+    pub fn encode_with_options<W: Write>(
+        &mut self,
+        mut writer: W,
+        options: &EncodeOptions,
+    ) -> IoResult<()> {
+        if options.reject_non_finite {
+            if let Some(field) = self.find_non_finite() {
+                let msg = format!("non-finite value in {field}");
+
+                return Err(IoError::new(ErrorKind::InvalidInput, msg));
+            }
+        }
+
+        if options.write_bom {
+            writer.write_all(options.encoding.bom())?;
+        }
+
+        if options.encoding == Encoding::Utf8 {
+            return self.encode_content(writer, options);
+        }
+
+        let mut bytes = Vec::with_capacity(4096);
+        self.encode_content(&mut bytes, options)?;
+
+        let content =
+            String::from_utf8(bytes).map_err(|err| IoError::new(ErrorKind::InvalidData, err))?;
+
+        let to_bytes = if options.encoding == Encoding::Utf16LE {
+            u16::to_le_bytes
+        } else {
+            u16::to_be_bytes
+        };
+
+        let utf16: Vec<_> = content.encode_utf16().flat_map(to_bytes).collect();
+        writer.write_all(&utf16)?;
+
+        writer.flush()
+    }
+
+    /// Returns the name of the first value that is NaN or infinite.
+    fn find_non_finite(&self) -> Option<&'static str> {
+        let difficulty = [
+            ("HPDrainRate", self.hp_drain_rate),
+            ("CircleSize", self.circle_size),
+            ("OverallDifficulty", self.overall_difficulty),
+            ("ApproachRate", self.approach_rate),
+        ];
+
+        if let Some((field, _)) = difficulty.iter().find(|(_, value)| !value.is_finite()) {
+            return Some(field);
+        }
+
+        let slider_values = [
+            ("SliderMultiplier", self.slider_multiplier),
+            ("SliderTickRate", self.slider_tick_rate),
+        ];
+
+        if let Some((field, _)) = slider_values.iter().find(|(_, value)| !value.is_finite()) {
+            return Some(field);
+        }
+
+        let control_points = &self.control_points;
+
+        let timing_points = control_points
+            .timing_points
+            .iter()
+            .all(|point| point.time.is_finite() && point.beat_len.is_finite());
+
+        let difficulty_points = control_points
+            .difficulty_points
+            .iter()
+            .all(|point| point.time.is_finite() && point.slider_velocity.is_finite());
+
+        let effect_points = control_points
+            .effect_points
+            .iter()
+            .all(|point| point.time.is_finite() && point.scroll_speed.is_finite());
+
+        let sample_points = control_points
+            .sample_points
+            .iter()
+            .all(|point| point.time.is_finite());
+
+        if !(timing_points && difficulty_points && effect_points && sample_points) {
+            return Some("control points");
+        }
+
+        let is_finite_pos = |pos: Pos| pos.x.is_finite() && pos.y.is_finite();
+
+        let hit_objects = self.hit_objects.iter().all(|h| {
+            let kind = match h.kind {
+                HitObjectKind::Circle(ref h) => is_finite_pos(h.pos),
+                HitObjectKind::Slider(ref h) => {
+                    is_finite_pos(h.pos)
+                        && h.velocity.is_finite()
+                        && h.path.expected_dist().is_none_or(f64::is_finite)
+                        && h.path
+                            .control_points()
+                            .iter()
+                            .all(|point| is_finite_pos(point.pos))
+                }
+                HitObjectKind::Spinner(ref h) => is_finite_pos(h.pos) && h.duration.is_finite(),
+                HitObjectKind::Hold(ref h) => h.pos_x.is_finite() && h.duration.is_finite(),
+            };
+
+            h.start_time.is_finite() && kind
+        });
+
+        (!hit_objects).then_some("hit objects")
+    }
+
+    fn encode_content<W: Write>(&mut self, mut writer: W, options: &EncodeOptions) -> IoResult<()> {
+        writeln!(writer, "{}", FormatVersionHeader(self.format_version))?;
 
         writer.write_all(b"\n")?;
         self.encode_general(&mut writer)?;
@@ -121,10 +301,10 @@ impl Beatmap {
         self.encode_metadata(&mut writer)?;
 
         writer.write_all(b"\n")?;
-        self.encode_difficulty(&mut writer)?;
+        self.encode_difficulty(&mut writer, options)?;
 
         writer.write_all(b"\n")?;
-        self.encode_events(&mut writer)?;
+        self.encode_events(&mut writer, options)?;
 
         writer.write_all(b"\n")?;
         self.encode_timing_points(&mut writer)?;
@@ -138,6 +318,73 @@ impl Beatmap {
         writer.flush()
     }
 
+    /// Encode the [`Beatmap`]'s header into content of a `.osu` file.
+    ///
+    /// Only the `[General]`, `[Editor]`, `[Metadata]`, `[Difficulty]`, and
+    /// `[TimingPoints]` sections are written, making it a lightweight
+    /// skeleton for previews.
+    ///
+    /// Note that sample points are not reconstructed from hit objects so if
+    /// the beatmap has no sample points, the timing points won't contain
+    /// sample info.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rosu_map::Beatmap;
+    /// let map: Beatmap = /* ... */
+    /// # Beatmap::default();
+    /// let header: String = map.encode_header_only();
+    /// assert!(!header.contains("[HitObjects]"));
+    /// ```
+    pub fn encode_header_only(&self) -> String {
+        fn encode_header<W: Write>(map: &Beatmap, mut writer: W) -> IoResult<()> {
+            writeln!(writer, "{}", FormatVersionHeader(map.format_version))?;
+
+            writer.write_all(b"\n")?;
+            map.encode_general(&mut writer)?;
+
+            writer.write_all(b"\n")?;
+            map.encode_editor(&mut writer)?;
+
+            writer.write_all(b"\n")?;
+            map.encode_metadata(&mut writer)?;
+
+            writer.write_all(b"\n")?;
+            map.encode_difficulty(&mut writer, &EncodeOptions::default())?;
+
+            writer.write_all(b"\n")?;
+            write_control_points(&mut writer, &map.control_points)
+        }
+
+        let mut writer = Vec::with_capacity(1024);
+
+        // Writing into a `Vec<u8>` does not fail
+        let _ = encode_header(self, &mut writer);
+
+        match String::from_utf8(writer) {
+            Ok(content) => content,
+            Err(err) => String::from_utf8_lossy(err.as_bytes()).into_owned(),
+        }
+    }
+
+    /// Calculate the MD5 hash of the encoded [`Beatmap`] as lowercase hex
+    /// string.
+    ///
+    /// Note that this hashes the *re-encoded* content which may differ from
+    /// the original file, e.g. due to formatting or omitted data, so the
+    /// hash does not necessarily match the one osu! uses for the original
+    /// file. If the original bytes are available, prefer
+    /// [`md5_of_bytes`](crate::md5_of_bytes).
+    pub fn compute_md5(&mut self) -> String {
+        let mut writer = Vec::with_capacity(4096);
+
+        // Writing into a `Vec<u8>` does not fail
+        let _ = self.encode(&mut writer);
+
+        crate::md5_of_bytes(&writer)
+    }
+
     fn encode_general<W: Write>(&self, writer: &mut W) -> IoResult<()> {
         writeln!(
             writer,
@@ -147,7 +394,7 @@ impl Beatmap {
 {}: {}
 {}: {}",
             GeneralKey::AudioFilename,
-            self.audio_file,
+            self.audio_file.to_osu_path(),
             GeneralKey::AudioLeadIn,
             self.audio_lead_in,
             GeneralKey::PreviewTime,
@@ -156,22 +403,22 @@ impl Beatmap {
             self.countdown as i32
         )?;
 
-        let sample_set = self
-            .control_points
-            .sample_points
-            .first()
-            .map_or(SamplePoint::DEFAULT_SAMPLE_BANK, |sample_point| {
-                sample_point.sample_bank
-            });
-
+        // osu!lazer writes the first sample point's bank as sample set because
+        // it does not store the decoded value. Since the first sample point
+        // may differ from it, writing that instead would not round-trip.
+        // Likewise, `SampleVolume` is not written by osu!lazer but is required
+        // for `default_sample_volume` to round-trip.
         writeln!(
             writer,
             "{}: {}
 {}: {}
 {}: {}
+{}: {}
 {}: {}",
             GeneralKey::SampleSet,
-            sample_set as i32,
+            self.default_sample_bank as i32,
+            GeneralKey::SampleVolume,
+            self.default_sample_volume,
             GeneralKey::StackLeniency,
             self.stack_leniency,
             GeneralKey::Mode,
@@ -284,18 +531,30 @@ impl Beatmap {
             writeln!(writer, "{}: {}", MetadataKey::Tags, &self.tags)?;
         }
 
+        // Same as osu!lazer except that an ID of 0 is written too because it
+        // differs from the default of -1 and would otherwise not round-trip.
+        if self.beatmap_id >= 0 {
+            writeln!(writer, "{}: {}", MetadataKey::BeatmapID, self.beatmap_id)?;
+        }
+
+        if self.beatmap_set_id > 0 {
+            writeln!(
+                writer,
+                "{}: {}",
+                MetadataKey::BeatmapSetID,
+                self.beatmap_set_id
+            )?;
+        }
+
         Ok(())
     }
 
-    fn encode_difficulty<W: Write>(&self, writer: &mut W) -> IoResult<()> {
+    fn encode_difficulty<W: Write>(&self, writer: &mut W, options: &EncodeOptions) -> IoResult<()> {
         writeln!(
             writer,
             "[Difficulty]
 {}: {}
 {}: {}
-{}: {}
-{}: {}
-{}: {}
 {}: {}",
             DifficultyKey::HPDrainRate,
             self.hp_drain_rate,
@@ -303,8 +562,24 @@ impl Beatmap {
             self.circle_size,
             DifficultyKey::OverallDifficulty,
             self.overall_difficulty,
-            DifficultyKey::ApproachRate,
-            self.approach_rate,
+        )?;
+
+        let omit_approach_rate = options.omit_default_approach_rate
+            && self.approach_rate.to_bits() == self.overall_difficulty.to_bits();
+
+        if !omit_approach_rate {
+            writeln!(
+                writer,
+                "{}: {}",
+                DifficultyKey::ApproachRate,
+                self.approach_rate
+            )?;
+        }
+
+        writeln!(
+            writer,
+            "{}: {}
+{}: {}",
             DifficultyKey::SliderMultiplier,
             self.slider_multiplier,
             DifficultyKey::SliderTickRate,
@@ -312,103 +587,80 @@ impl Beatmap {
         )
     }
 
-    fn encode_events<W: Write>(&self, writer: &mut W) -> IoResult<()> {
+    fn encode_events<W: Write>(&self, writer: &mut W, options: &EncodeOptions) -> IoResult<()> {
         writer.write_all(b"[Events]\n")?;
 
+        let event_type = |kind| EventTypeDisplay(kind, options.event_type_style);
+
         if !self.background_file.is_empty() {
             writeln!(
                 writer,
                 "{},0,\"{}\",0,0",
-                EventType::Background as i32,
-                self.background_file
+                event_type(EventType::Background),
+                self.background_file.to_osu_path()
             )?;
         }
 
-        for b in self.breaks.iter() {
+        let mut breaks: Vec<_> = self.breaks.iter().collect();
+        breaks.sort_by(|a, b| a.start_time.total_cmp(&b.start_time));
+
+        for b in breaks {
             writeln!(
                 writer,
                 "{},{},{}",
-                EventType::Break as i32,
+                event_type(EventType::Break),
                 b.start_time,
                 b.end_time
             )?;
         }
 
-        Ok(())
-    }
+        let mut background_colors: Vec<_> = self.background_colors.iter().collect();
+        background_colors.sort_by(|(a, _), (b, _)| a.total_cmp(b));
 
-    fn encode_timing_points<W: Write>(&mut self, writer: &mut W) -> IoResult<()> {
-        fn output_control_point_at<W: Write>(
-            writer: &mut W,
-            props: &ControlPointProperties,
-            is_timing: bool,
-        ) -> IoResult<()> {
+        for (time, color) in background_colors {
             writeln!(
                 writer,
-                "{},{},{},{},{},{}",
-                props.timing_signature,
-                props.sample_bank,
-                props.custom_sample_bank,
-                props.sample_volume,
-                if is_timing { "1" } else { "0" },
-                props.effect_flags
-            )
+                "{},{time},{},{},{}",
+                event_type(EventType::Color),
+                color.red(),
+                color.green(),
+                color.blue()
+            )?;
         }
 
-        let mut control_points = self.control_points.clone();
-        collect_samples(self, &mut control_points);
+        for file in self.storyboard_files.iter() {
+            write!(writer, "{},", event_type(file.kind))?;
 
-        let mut groups: Vec<_> = control_points
-            .timing_points
-            .iter()
-            .map(ControlPointGroup::from)
-            .collect();
+            if file.kind == EventType::Sample {
+                write!(writer, "{},{}", file.time.unwrap_or(0.0), file.layer)?;
+            } else {
+                let origin = file.origin.as_deref().unwrap_or("Centre");
+                write!(writer, "{},{origin}", file.layer)?;
+            }
 
-        groups.sort_unstable_by(|a, b| a.time.total_cmp(&b.time));
+            write!(writer, ",\"{}\"", file.filename.to_osu_path())?;
 
-        let times = control_points
-            .difficulty_points
-            .iter()
-            .map(|point| point.time)
-            .chain(control_points.effect_points.iter().map(|point| point.time))
-            .chain(control_points.sample_points.iter().map(|point| point.time));
-
-        for time in times {
-            if let Err(i) = groups.binary_search_by(|probe| probe.time.total_cmp(&time)) {
-                groups.insert(i, ControlPointGroup::new(time));
+            if !file.params.is_empty() {
+                write!(writer, ",{}", file.params)?;
             }
+
+            writer.write_all(b"\n")?;
         }
 
-        writer.write_all(b"[TimingPoints]\n")?;
-        let mut last_props = ControlPointProperties::default();
-
-        for group in groups {
-            let props = ControlPointProperties::new(
-                group.time,
-                &control_points,
-                &last_props,
-                group.timing.is_some(),
-            );
-
-            if let Some(timing) = group.timing {
-                write!(writer, "{},{},", timing.time, timing.beat_len)?;
-                output_control_point_at(writer, &props, true)?;
-                last_props = ControlPointProperties {
-                    slider_velocity: 1.0,
-                    ..props
-                };
-            }
+        Ok(())
+    }
 
-            if props.is_redundant(&last_props) {
-                continue;
-            }
+    fn encode_timing_points<W: Write>(&mut self, writer: &mut W) -> IoResult<()> {
+        let mut control_points = self.control_points.clone();
 
-            write!(writer, "{},{},", group.time, -100.0 / props.slider_velocity)?;
-            output_control_point_at(writer, &props, false)?;
-            last_props = props;
+        // Decoded maps already carry their sample points so only fall back to
+        // reconstructing them from hit object samples if there are none.
+        // Without any timing points, there is nothing to attach them to.
+        if control_points.sample_points.is_empty() && !control_points.timing_points.is_empty() {
+            collect_samples(self, &mut control_points);
         }
 
-        Ok(())
+        write_control_points(writer, &control_points)
     }
 
     fn encode_colors<W: Write>(&self, writer: &mut W) -> IoResult<()> {
@@ -425,6 +677,24 @@ impl Beatmap {
             )?;
         }
 
+        let slider_colors = [
+            ("SliderTrackOverride", self.slider_track_override),
+            ("SliderBorder", self.slider_border),
+        ];
+
+        for (key, color) in slider_colors {
+            if let Some(color) = color {
+                writeln!(
+                    writer,
+                    "{key}: {},{},{},{}",
+                    color.red(),
+                    color.green(),
+                    color.blue(),
+                    color.alpha(),
+                )?;
+            }
+        }
+
         for custom in self.custom_colors.iter() {
             writeln!(
                 writer,
@@ -459,7 +729,7 @@ impl Beatmap {
                 y = pos.y,
                 start_time = hit_object.start_time,
                 kind = i32::from(HitObjectType::from(&*hit_object)),
-                sound = u8::from(HitSoundType::from(hit_object.samples.as_slice())),
+                sound = hit_sound_type(&hit_object.samples),
             )?;
 
             match hit_object.kind {
@@ -475,7 +745,11 @@ impl Beatmap {
                 }
             }
 
-            get_sample_bank(writer, &hit_object.samples, false, self.mode)?;
+            // Same leniency that decoding uses when applying sample points
+            let end_time = hit_object.end_time_with_bufs(&mut bufs) + 5.0;
+            let sample_point = self.control_points.sample_point_at(end_time);
+
+            get_sample_bank(writer, &hit_object.samples, false, self.mode, sample_point)?;
 
             writer.write_all(b"\n")?;
         }
@@ -484,9 +758,84 @@ impl Beatmap {
     }
 }
 
+fn write_control_points<W: Write>(writer: &mut W, control_points: &ControlPoints) -> IoResult<()> {
+    fn output_control_point_at<W: Write>(
+        writer: &mut W,
+        props: &ControlPointProperties,
+        is_timing: bool,
+    ) -> IoResult<()> {
+        writeln!(
+            writer,
+            "{},{},{},{},{},{}",
+            props.timing_signature,
+            props.sample_bank,
+            props.custom_sample_bank,
+            props.sample_volume,
+            if is_timing { "1" } else { "0" },
+            props.effect_flags
+        )
+    }
+
+    let mut groups: Vec<_> = control_points
+        .timing_points
+        .iter()
+        .map(ControlPointGroup::from)
+        .collect();
+
+    groups.sort_unstable_by(|a, b| a.time.total_cmp(&b.time));
+
+    let times = control_points
+        .difficulty_points
+        .iter()
+        .map(|point| point.time)
+        .chain(control_points.effect_points.iter().map(|point| point.time))
+        .chain(control_points.sample_points.iter().map(|point| point.time));
+
+    for time in times {
+        if let Err(i) = groups.binary_search_by(|probe| probe.time.total_cmp(&time)) {
+            groups.insert(i, ControlPointGroup::new(time));
+        }
+    }
+
+    writer.write_all(b"[TimingPoints]\n")?;
+    let mut last_props = ControlPointProperties::default();
+
+    for group in groups {
+        let props = ControlPointProperties::new(group.time, control_points, &last_props);
+
+        if let Some(timing) = group.timing {
+            write!(writer, "{},{},", timing.time, timing.beat_len)?;
+            output_control_point_at(writer, &props, true)?;
+            last_props = ControlPointProperties {
+                slider_velocity: DifficultyPoint::DEFAULT_SLIDER_VELOCITY,
+                generate_ticks: DifficultyPoint::DEFAULT_GENERATE_TICKS,
+                ..props
+            };
+        }
+
+        if props.is_redundant(&last_props) {
+            continue;
+        }
+
+        // A NaN beat length is how slider ticks are disabled
+        let beat_len = if props.generate_ticks {
+            -100.0 / props.slider_velocity
+        } else {
+            f64::NAN
+        };
+
+        write!(writer, "{},{beat_len},", group.time)?;
+        output_control_point_at(writer, &props, false)?;
+        last_props = props;
+    }
+
+    Ok(())
+}
+
 #[derive(Clone, Default)]
 struct ControlPointProperties {
     slider_velocity: f64,
+    generate_ticks: bool,
     timing_signature: u32,
     sample_bank: i32,
     custom_sample_bank: i32,
@@ -495,12 +844,7 @@ struct ControlPointProperties {
 }
 
 impl ControlPointProperties {
-    fn new(
-        time: f64,
-        control_points: &ControlPoints,
-        last_props: &Self,
-        update_sample_bank: bool,
-    ) -> Self {
+    fn new(time: f64, control_points: &ControlPoints, last_props: &Self) -> Self {
         let timing = control_points.timing_point_at(time);
         let difficulty = control_points.difficulty_point_at(time);
         let sample = control_points
@@ -527,17 +871,16 @@ impl ControlPointProperties {
             slider_velocity: difficulty.map_or(DifficultyPoint::DEFAULT_SLIDER_VELOCITY, |point| {
                 point.slider_velocity
             }),
+            generate_ticks: difficulty.map_or(DifficultyPoint::DEFAULT_GENERATE_TICKS, |point| {
+                point.generate_ticks
+            }),
             timing_signature: timing
                 .map_or(TimingPoint::DEFAULT_TIME_SIGNATURE, |point| {
                     point.time_signature
                 })
                 .numerator
                 .get(),
-            sample_bank: if update_sample_bank {
-                tmp_hit_sample.bank as i32
-            } else {
-                last_props.sample_bank
-            },
+            sample_bank: tmp_hit_sample.bank as i32,
             custom_sample_bank: if tmp_hit_sample.custom_sample_bank >= 0 {
                 tmp_hit_sample.custom_sample_bank
             } else {
@@ -550,6 +893,7 @@ impl ControlPointProperties {
 
     fn is_redundant(&self, other: &Self) -> bool {
         (self.slider_velocity - other.slider_velocity).abs() < f64::EPSILON
+            && self.generate_ticks == other.generate_ticks
             && self.timing_signature == other.timing_signature
             && self.sample_bank == other.sample_bank
             && self.custom_sample_bank == other.custom_sample_bank
@@ -563,7 +907,7 @@ struct ControlPointGroup<'a> {
     timing: Option<&'a TimingPoint>,
 }
 
-impl<'a> ControlPointGroup<'a> {
+impl ControlPointGroup<'_> {
     const fn new(time: f64) -> Self {
         Self { time, timing: None }
     }
@@ -652,10 +996,7 @@ fn add_path_data<W: Write>(
         }
     }
 
-    let dist = slider
-        .path
-        .expected_dist()
-        .unwrap_or_else(|| slider.path.curve_with_bufs(bufs).dist());
+    let dist = slider.path.expected_or_curve_dist(bufs);
 
     write!(
         writer,
@@ -682,7 +1023,7 @@ fn add_path_data<W: Write>(
 
     for i in 0..=slider.span_count() as usize {
         if i < slider.node_samples.len() {
-            get_sample_bank(writer, &slider.node_samples[i], true, mode)?;
+            get_sample_bank(writer, &slider.node_samples[i], true, mode, None)?;
         } else {
             writer.write_all(b"0:0")?;
         }
@@ -699,11 +1040,29 @@ fn add_path_data<W: Write>(
     Ok(())
 }
 
+/// Additions are converted into their flag. The [`HitSoundType::NORMAL`]
+/// flag is only added if additions are present and the normal sample is not
+/// layered, i.e. it was explicitly specified.
+fn hit_sound_type(samples: &[HitSampleInfo]) -> u8 {
+    let mut kind = u8::from(HitSoundType::from(samples));
+
+    let unlayered_normal = samples
+        .iter()
+        .any(|sample| sample.name == HitSampleInfo::HIT_NORMAL && !sample.is_layered);
+
+    if kind != HitSoundType::NONE && unlayered_normal {
+        kind |= HitSoundType::NORMAL;
+    }
+
+    kind
+}
+
 fn get_sample_bank<W: Write>(
     writer: &mut W,
     samples: &[HitSampleInfo],
     banks_only: bool,
     mode: GameMode,
+    sample_point: Option<&SamplePoint>,
 ) -> IoResult<()> {
     // osu!lazer throws an error if multiple samples match the filter but
     // we'll just take the first and assume it's the only one.
@@ -742,7 +1101,17 @@ fn get_sample_bank<W: Write>(
 
     let mut volume = samples.first().map_or(100, |sample| sample.volume);
 
-    if mode != GameMode::Mania {
+    // osu!lazer only writes these values for mania and otherwise relies on
+    // the sample points it reconstructed from hit objects. Since the
+    // beatmap's own sample points are encoded instead, the values are only
+    // omitted if the active sample point provides them anyway.
+    let default_point = SamplePoint::default();
+    let active_point = sample_point.unwrap_or(&default_point);
+
+    let omit = active_point.sample_volume.clamp(0, 100) == volume
+        && (custom_sample_bank == 0 || active_point.custom_sample_bank == custom_sample_bank);
+
+    if mode != GameMode::Mania && omit {
         custom_sample_bank = 0;
         volume = 0;
     }
@@ -770,9 +1139,8 @@ fn collect_samples(map: &mut Beatmap, control_points: &mut ControlPoints) {
             HitObjectKind::Circle(_) | HitObjectKind::Spinner(_) => {}
             HitObjectKind::Slider(ref mut slider) => match map.mode {
                 GameMode::Osu => {
-                    let events = slider_events(
+                    let events = slider.osu_events(
                         h.start_time,
-                        slider,
                         map.format_version,
                         map.slider_tick_rate,
                         &map.control_points,
@@ -844,17 +1212,14 @@ fn collect_samples(map: &mut Beatmap, control_points: &mut ControlPoints) {
     }
 
     collected_samples.sort_by(|a, b| a.time.total_cmp(&b.time));
-    let mut collected_samples = collected_samples.into_iter();
 
-    if let Some(sample) = collected_samples.next() {
-        control_points.add(sample.clone());
-        let mut last_sample = sample;
+    // Samples that match the defaults don't require a sample point
+    let mut last_sample = SamplePoint::default();
 
-        for sample in collected_samples {
-            if !sample.is_redundant(&last_sample) {
-                control_points.add(sample.clone());
-                last_sample = sample;
-            }
+    for sample in collected_samples {
+        if !sample.is_redundant(&last_sample) {
+            control_points.add(sample.clone());
+            last_sample = sample;
         }
     }
 }
@@ -871,15 +1236,22 @@ fn collect_sample(
     // We know the samples aren't empty so we can unwrap
     let volume = samples.iter().map(|sample| sample.volume).max().unwrap();
 
+    // File samples always have a custom sample bank of 1 so they're ignored
     let custom_idx = samples
         .iter()
+        .filter(|sample| matches!(sample.name, HitSampleInfoName::Default(_)))
         .map(|sample| sample.custom_sample_bank)
         .max()
-        .unwrap();
+        .unwrap_or(SamplePoint::DEFAULT_CUSTOM_SAMPLE_BANK);
+
+    let sample_bank = samples
+        .iter()
+        .find(|sample| sample.name == HitSampleInfo::HIT_NORMAL)
+        .map_or(SamplePoint::DEFAULT_SAMPLE_BANK, |sample| sample.bank);
 
     let sample = SamplePoint {
         time: end_time,
-        sample_bank: SamplePoint::DEFAULT_SAMPLE_BANK,
+        sample_bank,
         sample_volume: volume,
         custom_sample_bank: custom_idx,
     };
@@ -887,56 +1259,6 @@ fn collect_sample(
     collected_samples.push(sample);
 }
 
-fn slider_events<'ticks>(
-    start_time: f64,
-    slider: &mut HitObjectSlider,
-    format_version: i32,
-    slider_tick_rate: f64,
-    control_points: &ControlPoints,
-    curve_bufs: &mut CurveBuffers,
-    ticks: &'ticks mut Vec<SliderEvent>,
-) -> SliderEventsIter<'ticks> {
-    let beat_len = control_points
-        .timing_point_at(start_time)
-        .map_or(TimingPoint::DEFAULT_BEAT_LEN, |point| point.beat_len);
-
-    let (slider_velocity, generate_ticks) = control_points.difficulty_point_at(start_time).map_or(
-        (
-            DifficultyPoint::DEFAULT_SLIDER_VELOCITY,
-            DifficultyPoint::DEFAULT_GENERATE_TICKS,
-        ),
-        |point| (point.slider_velocity, point.generate_ticks),
-    );
-
-    let tick_dist_multiplier = if format_version < 8 {
-        slider_velocity.recip()
-    } else {
-        1.0
-    };
-
-    let scoring_dist = slider.velocity * beat_len;
-
-    let tick_dist = if generate_ticks {
-        scoring_dist / slider_tick_rate * tick_dist_multiplier
-    } else {
-        f64::INFINITY
-    };
-
-    let dist = slider.path.curve_with_bufs(curve_bufs).dist();
-    let span_count = slider.span_count();
-    let span_duration = slider.duration_with_bufs(curve_bufs) / f64::from(span_count);
-
-    SliderEventsIter::new(
-        start_time,
-        span_duration,
-        slider.velocity,
-        tick_dist,
-        dist,
-        span_count,
-        ticks,
-    )
-}
-
 #[allow(clippy::too_many_arguments)]
 fn juicestream_events<'ticks>(
     start_time: f64,
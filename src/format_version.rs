@@ -1,4 +1,7 @@
-use std::ops::ControlFlow;
+use std::{
+    fmt::{Display, Formatter, Result as FmtResult},
+    ops::ControlFlow,
+};
 
 use crate::util::{ParseNumber, ParseNumberError};
 
@@ -8,23 +11,35 @@ const VERSION_PREFIX: &str = "osu file format v";
 pub const LATEST_FORMAT_VERSION: i32 = 14;
 
 pub(crate) fn try_version_from_line(line: &str) -> ControlFlow<Result<i32, ParseVersionError>, ()> {
-    if !line.starts_with(VERSION_PREFIX) {
+    // Remnants of a byte order mark or surrounding whitespace are ignored
+    let line = line.trim_start_matches('\u{feff}').trim();
+
+    let Some(version) = line.strip_prefix(VERSION_PREFIX) else {
         return if line.is_empty() {
             ControlFlow::Continue(())
         } else {
             ControlFlow::Break(Err(ParseVersionError::UnknownFileFormat))
         };
-    }
+    };
 
-    let res = match line.rsplit('v').next().map(i32::parse) {
-        Some(Ok(version)) => Ok(version),
-        Some(Err(err)) => Err(err.into()),
-        None => Ok(LATEST_FORMAT_VERSION),
+    let res = if version.is_empty() {
+        Ok(LATEST_FORMAT_VERSION)
+    } else {
+        i32::parse(version).map_err(ParseVersionError::from)
     };
 
     ControlFlow::Break(res)
 }
 
+/// Displays the header line `osu file format v{version}` of a `.osu` file.
+pub(crate) struct FormatVersionHeader(pub(crate) i32);
+
+impl Display for FormatVersionHeader {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{VERSION_PREFIX}{}", self.0)
+    }
+}
+
 thiserror! {
     /// All the ways that parsing the format version can fail.
     #[derive(Debug)]
@@ -49,6 +64,30 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn trailing_whitespace() {
+        let line = "osu file format v14 ";
+        assert!(matches!(
+            try_version_from_line(line),
+            ControlFlow::Break(Ok(14))
+        ));
+
+        let line = "\u{feff} osu file format v14\t";
+        assert!(matches!(
+            try_version_from_line(line),
+            ControlFlow::Break(Ok(14))
+        ));
+    }
+
+    #[test]
+    fn future_version() {
+        let line = "osu file format v128";
+        assert!(matches!(
+            try_version_from_line(line),
+            ControlFlow::Break(Ok(128))
+        ));
+    }
+
     #[test]
     fn fails_on_comment() {
         let line = "osu file format v42 // comment";
@@ -66,4 +105,15 @@ mod tests {
             ControlFlow::Break(Err(ParseVersionError::UnknownFileFormat))
         ));
     }
+
+    #[test]
+    fn header_roundtrip() {
+        let header = FormatVersionHeader(LATEST_FORMAT_VERSION).to_string();
+        assert_eq!(header, "osu file format v14");
+
+        assert!(matches!(
+            try_version_from_line(&header),
+            ControlFlow::Break(Ok(LATEST_FORMAT_VERSION))
+        ));
+    }
 }
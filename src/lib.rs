@@ -27,7 +27,7 @@
 //! ## How
 //!
 //! The simplest way to make use of a type's [`DecodeBeatmap`] implementation is by using
-//! `rosu-map`s functions [`from_bytes`], [`from_path`], and [`from_str`].
+//! `rosu-map`s functions [`from_bytes`], [`from_path`], [`from_reader`], and [`from_str`].
 //!
 //! ```
 //! # use rosu_map::Beatmap;
@@ -81,7 +81,8 @@
 //! | Flag | Description | Dependencies
 //! | - | - | -
 //! | `default` | No features |
-//! | `tracing` | Any error encountered during decoding will be logged through `tracing::error`. If this features is not enabled, errors will be ignored. | [`tracing`]
+//! | `tracing` | Any error encountered during decoding will be logged through `tracing::error` and other issues such as clamped values through `tracing::warn`. If this features is not enabled, errors will be ignored. | [`tracing`]
+//! | `serde` | Implements `serde::Serialize` and `serde::Deserialize` for `Beatmap` and all section types. The calculated curve of a `SliderPath` is skipped. | [`serde`]
 //!
 //! ## Misc
 //!
@@ -110,6 +111,7 @@
 //! [`from_bytes`]: crate::decode::from_bytes
 //! [`from_str`]: crate::decode::from_str
 //! [`from_path`]: crate::decode::from_path
+//! [`from_reader`]: crate::decode::from_reader
 //! [`General`]: crate::section::general::decode::General
 //! [`Editor`]: crate::section::editor::Editor
 //! [`Metadata`]: crate::section::metadata::Metadata
@@ -117,6 +119,7 @@
 //! [`TimingPoints`]: crate::section::timing_points::decode::TimingPoints
 //! [`HitObjects`]: crate::section::hit_objects::decode::HitObjects
 //! [`tracing`]: https://docs.rs/tracing
+//! [`serde`]: https://docs.rs/serde
 //! [`rosu-storyboard`]: https://github.com/MaxOhn/rosu-storyboard/
 
 #![deny(rustdoc::broken_intra_doc_links, rustdoc::missing_crate_level_docs)]
@@ -140,6 +143,7 @@ mod beatmap;
 mod decode;
 mod encode;
 mod format_version;
+mod md5;
 mod reader;
 
 /// Section-specific types.
@@ -149,7 +153,12 @@ pub mod section;
 pub mod util;
 
 pub use crate::{
-    beatmap::{Beatmap, BeatmapState, ParseBeatmapError},
-    decode::{from_bytes, from_path, from_str, DecodeBeatmap, DecodeState},
+    beatmap::{Beatmap, BeatmapState, HitObjectsGuard, ParseBeatmapError},
+    decode::{
+        from_bytes, from_path, from_reader, from_str, DecodeBeatmap, DecodeOptions, DecodeState,
+    },
+    encode::{EncodeOptions, EventTypeStyle},
     format_version::LATEST_FORMAT_VERSION,
+    md5::md5_of_bytes,
+    reader::Encoding,
 };
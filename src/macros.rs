@@ -201,6 +201,13 @@ macro_rules! thiserror {
         }
     };
 
+	( @ENUMFMT $self_:ident, $fmt:ident, $desc:literal, $variant_name:ident { $( $sf:ident: $st:ty ),* } ) => {
+        #[allow(unused_variables)]
+        if let Self::$variant_name { $( $sf ),* } = $self_ {
+            return write!($fmt, $desc);
+        }
+    };
+
     ( @ENUMFROM $e:ident, $variant_name:ident ( #[from] $t:ty ) ) => {
         impl From<$t> for $e {
             fn from(x: $t) -> $e {
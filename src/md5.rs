@@ -0,0 +1,176 @@
+use std::fmt::Write;
+
+const S: [u32; 64] = [
+    7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22, 5, 9, 14, 20, 5, 9, 14, 20, 5, 9,
+    14, 20, 5, 9, 14, 20, 4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23, 6, 10, 15,
+    21, 6, 10, 15, 21, 6, 10, 15, 21, 6, 10, 15, 21,
+];
+
+const K: [u32; 64] = [
+    0xd76a_a478,
+    0xe8c7_b756,
+    0x2420_70db,
+    0xc1bd_ceee,
+    0xf57c_0faf,
+    0x4787_c62a,
+    0xa830_4613,
+    0xfd46_9501,
+    0x6980_98d8,
+    0x8b44_f7af,
+    0xffff_5bb1,
+    0x895c_d7be,
+    0x6b90_1122,
+    0xfd98_7193,
+    0xa679_438e,
+    0x49b4_0821,
+    0xf61e_2562,
+    0xc040_b340,
+    0x265e_5a51,
+    0xe9b6_c7aa,
+    0xd62f_105d,
+    0x0244_1453,
+    0xd8a1_e681,
+    0xe7d3_fbc8,
+    0x21e1_cde6,
+    0xc337_07d6,
+    0xf4d5_0d87,
+    0x455a_14ed,
+    0xa9e3_e905,
+    0xfcef_a3f8,
+    0x676f_02d9,
+    0x8d2a_4c8a,
+    0xfffa_3942,
+    0x8771_f681,
+    0x6d9d_6122,
+    0xfde5_380c,
+    0xa4be_ea44,
+    0x4bde_cfa9,
+    0xf6bb_4b60,
+    0xbebf_bc70,
+    0x289b_7ec6,
+    0xeaa1_27fa,
+    0xd4ef_3085,
+    0x0488_1d05,
+    0xd9d4_d039,
+    0xe6db_99e5,
+    0x1fa2_7cf8,
+    0xc4ac_5665,
+    0xf429_2244,
+    0x432a_ff97,
+    0xab94_23a7,
+    0xfc93_a039,
+    0x655b_59c3,
+    0x8f0c_cc92,
+    0xffef_f47d,
+    0x8584_5dd1,
+    0x6fa8_7e4f,
+    0xfe2c_e6e0,
+    0xa301_4314,
+    0x4e08_11a1,
+    0xf753_7e82,
+    0xbd3a_f235,
+    0x2ad7_d2bb,
+    0xeb86_d391,
+];
+
+/// Calculate the MD5 hash of the given bytes as lowercase hex string.
+///
+/// This is the checksum that osu! and its API use to identify `.osu` files.
+///
+/// # Example
+///
+/// ```
+/// assert_eq!(
+///     rosu_map::md5_of_bytes(b"osu file format v14\n"),
+///     "36f70c48e16e2b13c3c93609eafdaff9",
+/// );
+/// ```
+pub fn md5_of_bytes(bytes: &[u8]) -> String {
+    let mut state: [u32; 4] = [0x6745_2301, 0xefcd_ab89, 0x98ba_dcfe, 0x1032_5476];
+
+    let bit_len = (bytes.len() as u64).wrapping_mul(8);
+
+    // Pad with a single `1` bit, then zeros until the length is 56 mod 64,
+    // and finally the message length in bits.
+    let mut tail = Vec::with_capacity(128);
+    tail.extend_from_slice(&bytes[bytes.len() - bytes.len() % 64..]);
+    tail.push(0x80);
+
+    while tail.len() % 64 != 56 {
+        tail.push(0);
+    }
+
+    tail.extend_from_slice(&bit_len.to_le_bytes());
+
+    let full_chunks = bytes[..bytes.len() - bytes.len() % 64].chunks_exact(64);
+
+    for chunk in full_chunks.chain(tail.chunks_exact(64)) {
+        process_chunk(&mut state, chunk);
+    }
+
+    state.iter().flat_map(|word| word.to_le_bytes()).fold(
+        String::with_capacity(32),
+        |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+
+            hex
+        },
+    )
+}
+
+// Naming follows RFC 1321
+#[allow(clippy::many_single_char_names)]
+fn process_chunk(state: &mut [u32; 4], chunk: &[u8]) {
+    let mut words = [0; 16];
+
+    for (word, bytes) in words.iter_mut().zip(chunk.chunks_exact(4)) {
+        *word = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    }
+
+    let [mut a, mut b, mut c, mut d] = *state;
+
+    for i in 0..64 {
+        let (f, g) = match i {
+            0..=15 => ((b & c) | (!b & d), i),
+            16..=31 => ((d & b) | (!d & c), (5 * i + 1) % 16),
+            32..=47 => (b ^ c ^ d, (3 * i + 5) % 16),
+            _ => (c ^ (b | !d), (7 * i) % 16),
+        };
+
+        let f = f.wrapping_add(a).wrapping_add(K[i]).wrapping_add(words[g]);
+
+        a = d;
+        d = c;
+        c = b;
+        b = b.wrapping_add(f.rotate_left(S[i]));
+    }
+
+    state[0] = state[0].wrapping_add(a);
+    state[1] = state[1].wrapping_add(b);
+    state[2] = state[2].wrapping_add(c);
+    state[3] = state[3].wrapping_add(d);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_hashes() {
+        assert_eq!(md5_of_bytes(b""), "d41d8cd98f00b204e9800998ecf8427e");
+        assert_eq!(
+            md5_of_bytes(b"The quick brown fox jumps over the lazy dog"),
+            "9e107d9d372bb6826bd81d3542a419d6"
+        );
+
+        // Input lengths around chunk boundaries
+        assert_eq!(
+            md5_of_bytes(&[b'a'; 56]),
+            "3b0c8ac703f828b04c6c197006d17218"
+        );
+        assert_eq!(
+            md5_of_bytes(&[b'a'; 64]),
+            "014842d480b571495a4a0363793f7367"
+        );
+    }
+}
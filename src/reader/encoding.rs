@@ -2,6 +2,10 @@ use std::str::{from_utf8 as str_from_utf8, from_utf8_unchecked as str_from_utf8_
 
 use super::u16_iter::{U16BeIterator, U16LeIterator};
 
+/// The text encoding of a `.osu` file.
+///
+/// Decoding detects the encoding through a byte order mark and assumes UTF-8
+/// if none is present.
 #[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
 pub enum Encoding {
     #[default]
@@ -11,7 +15,7 @@ pub enum Encoding {
 }
 
 impl Encoding {
-    pub const fn from_bom(bom: &[u8]) -> (Self, usize) {
+    pub(crate) const fn from_bom(bom: &[u8]) -> (Self, usize) {
         match bom {
             [0xEF, 0xBB, 0xBF, ..] => (Self::Utf8, 3),
             [0xFF, 0xFE, ..] => (Self::Utf16LE, 2),
@@ -20,10 +24,19 @@ impl Encoding {
         }
     }
 
+    /// The byte order mark of the encoding.
+    pub(crate) const fn bom(self) -> &'static [u8] {
+        match self {
+            Self::Utf8 => &[0xEF, 0xBB, 0xBF],
+            Self::Utf16BE => &[0xFE, 0xFF],
+            Self::Utf16LE => &[0xFF, 0xFE],
+        }
+    }
+
     /// Decodes the given `src` and returns it as a `&str`.
     ///
     /// In case of UTF-16 or invalid UTF-8, the result will be stored in `dst`.
-    pub fn decode<'a>(self, mut src: &'a [u8], dst: &'a mut String) -> &'a str {
+    pub(crate) fn decode<'a>(self, mut src: &'a [u8], dst: &'a mut String) -> &'a str {
         match self {
             Self::Utf8 => match str_from_utf8(src) {
                 Ok(s) => s,
@@ -1,4 +1,5 @@
 pub(crate) use self::decoder::Decoder;
+pub use self::encoding::Encoding;
 
 mod decoder;
 mod encoding;
@@ -87,16 +87,16 @@ mod tests {
     #[test]
     fn le_works() {
         let mut iter = U16LeIterator::new(&[b'1', 0, b'Z', 0]);
-        assert_eq!(iter.next(), Some(b'1' as u16));
-        assert_eq!(iter.next(), Some(b'Z' as u16));
+        assert_eq!(iter.next(), Some(u16::from(b'1')));
+        assert_eq!(iter.next(), Some(u16::from(b'Z')));
         assert_eq!(iter.next(), None);
     }
 
     #[test]
     fn be_works() {
         let mut iter = U16BeIterator::new(&[0, b'1', 0, b'Z']);
-        assert_eq!(iter.next(), Some(b'1' as u16));
-        assert_eq!(iter.next(), Some(b'Z' as u16));
+        assert_eq!(iter.next(), Some(u16::from(b'1')));
+        assert_eq!(iter.next(), Some(u16::from(b'Z')));
         assert_eq!(iter.next(), None);
     }
 }
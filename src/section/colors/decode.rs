@@ -11,9 +11,12 @@ use super::{Color, CustomColor};
 
 /// Struct containing all data from a `.osu` file's `[Colours]` section.
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Colors {
     pub custom_combo_colors: Vec<Color>,
     pub custom_colors: Vec<CustomColor>,
+    pub slider_track_override: Option<Color>,
+    pub slider_border: Option<Color>,
 }
 
 impl From<Colors> for Beatmap {
@@ -21,6 +24,8 @@ impl From<Colors> for Beatmap {
         Self {
             custom_combo_colors: colors.custom_combo_colors,
             custom_colors: colors.custom_colors,
+            slider_track_override: colors.slider_track_override,
+            slider_border: colors.slider_border,
             ..Self::default()
         }
     }
@@ -39,6 +44,8 @@ impl Colors {
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum ColorsKey {
     Combo,
+    SliderTrackOverride,
+    SliderBorder,
     Name(String),
 }
 
@@ -46,10 +53,11 @@ impl FromStr for ColorsKey {
     type Err = UnknownKeyError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.starts_with("Combo") {
-            Ok(Self::Combo)
-        } else {
-            Ok(Self::Name(s.to_owned()))
+        match s {
+            "SliderTrackOverride" => Ok(Self::SliderTrackOverride),
+            "SliderBorder" => Ok(Self::SliderBorder),
+            _ if s.starts_with("Combo") => Ok(Self::Combo),
+            _ => Ok(Self::Name(s.to_owned())),
         }
     }
 }
@@ -60,6 +68,8 @@ thiserror! {
     pub enum ParseColorsError {
         #[error("color specified in incorrect format (should be R,G,B or R,G,B,A)")]
         IncorrectColor,
+        #[error("hex color specified in incorrect format (should be RRGGBB or RRGGBBAA)")]
+        IncorrectHexColor,
         #[error("failed to parse number")]
         Number(#[source] ParseNumberError),
     }
@@ -117,6 +127,8 @@ impl DecodeBeatmap for Colors {
 
         match key {
             ColorsKey::Combo => state.custom_combo_colors.push(color),
+            ColorsKey::SliderTrackOverride => state.slider_track_override = Some(color),
+            ColorsKey::SliderBorder => state.slider_border = Some(color),
             ColorsKey::Name(name) => {
                 match state.custom_colors.iter_mut().find(|c| c.name == name) {
                     Some(old) => old.color = color,
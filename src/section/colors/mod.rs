@@ -1,4 +1,5 @@
 use std::{
+    fmt::{Display, Formatter, Result as FmtResult},
     ops::{Index, IndexMut},
     str::FromStr,
 };
@@ -9,6 +10,7 @@ pub(crate) mod decode; // pub(crate) for intradoc-links
 
 /// Basic RGBA color.
 #[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Color(pub [u8; 4]);
 
 impl Color {
@@ -36,6 +38,68 @@ impl Color {
     pub fn alpha(self) -> u8 {
         self[3]
     }
+
+    /// Linearly interpolate each channel between `self` and `other`.
+    ///
+    /// `t` is clamped between `0.0`, resulting in `self`, and `1.0`,
+    /// resulting in `other`.
+    #[must_use]
+    pub fn blend(self, other: Self, t: f32) -> Self {
+        let t = t.clamp(0.0, 1.0);
+
+        let lerp = |a: u8, b: u8| {
+            let (a, b) = (f32::from(a), f32::from(b));
+
+            // The result is guaranteed to be between `a` and `b`
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            {
+                (a + (b - a) * t).round() as u8
+            }
+        };
+
+        Self([
+            lerp(self[0], other[0]),
+            lerp(self[1], other[1]),
+            lerp(self[2], other[2]),
+            lerp(self[3], other[3]),
+        ])
+    }
+
+    /// Parse a hex string of the form `RRGGBB` or `RRGGBBAA` with an optional
+    /// leading `#`.
+    ///
+    /// Shorthand forms such as `#fff` are rejected.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rosu_map::section::colors::Color;
+    ///
+    /// let color = Color::from_hex("#8EC7FF").unwrap();
+    /// assert_eq!(color, Color::new(142, 199, 255, 255));
+    /// assert_eq!(color.to_string(), "8ec7ff");
+    /// ```
+    pub fn from_hex(s: &str) -> Result<Self, ParseColorsError> {
+        let hex = s.strip_prefix('#').unwrap_or(s);
+
+        if !matches!(hex.len(), 6 | 8) || !hex.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+            return Err(ParseColorsError::IncorrectHexColor);
+        }
+
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16);
+
+        let alpha = if hex.len() == 8 { channel(6)? } else { 255 };
+
+        Ok(Self::new(channel(0)?, channel(2)?, channel(4)?, alpha))
+    }
+
+    /// Replace the alpha value.
+    #[must_use]
+    pub const fn with_alpha(self, a: u8) -> Self {
+        let [r, g, b, _] = self.0;
+
+        Self([r, g, b, a])
+    }
 }
 
 impl Index<usize> for Color {
@@ -58,6 +122,23 @@ impl From<[u8; 4]> for Color {
     }
 }
 
+impl Display for Color {
+    /// Writes the color as lowercase hex string `rrggbb`, followed by the
+    /// alpha value if it is not `255`.
+    #[allow(clippy::many_single_char_names)]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let [r, g, b, a] = self.0;
+
+        write!(f, "{r:02x}{g:02x}{b:02x}")?;
+
+        if a != 255 {
+            write!(f, "{a:02x}")?;
+        }
+
+        Ok(())
+    }
+}
+
 impl FromStr for Color {
     type Err = ParseColorsError;
 
@@ -80,7 +161,56 @@ impl FromStr for Color {
 
 /// A combination of a [`Color`] and a name.
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CustomColor {
     pub name: String,
     pub color: Color,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blend() {
+        let red = Color::new(255, 0, 0, 255);
+        let blue = Color::new(0, 0, 255, 255);
+
+        assert_eq!(red.blend(blue, 0.5), Color::new(128, 0, 128, 255));
+        assert_eq!(red.blend(blue, 0.0), red);
+        assert_eq!(red.blend(blue, 2.0), blue);
+    }
+
+    #[test]
+    fn from_hex() {
+        assert_eq!(
+            Color::from_hex("8ec7ff").unwrap(),
+            Color::new(142, 199, 255, 255)
+        );
+        assert!(matches!(
+            Color::from_hex("#fff"),
+            Err(ParseColorsError::IncorrectHexColor)
+        ));
+        assert!(matches!(
+            Color::from_hex("+fffff"),
+            Err(ParseColorsError::IncorrectHexColor)
+        ));
+    }
+
+    #[test]
+    fn hex_roundtrip() {
+        let color = Color::new(18, 124, 255, 128);
+        let hex = color.to_string();
+
+        assert_eq!(hex, "127cff80");
+        assert_eq!(Color::from_hex(&hex).unwrap(), color);
+        assert_eq!(Color::new(255, 0, 0, 255).to_string(), "ff0000");
+    }
+
+    #[test]
+    fn with_alpha() {
+        let color = Color::new(10, 20, 30, 255).with_alpha(100);
+
+        assert_eq!(color, Color::new(10, 20, 30, 100));
+    }
+}
@@ -6,6 +6,7 @@ use crate::{
 
 /// Struct containing all data from a `.osu` file's `[Difficulty]` section.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Difficulty {
     pub hp_drain_rate: f32,
     pub circle_size: f32,
@@ -28,6 +29,153 @@ impl Default for Difficulty {
     }
 }
 
+impl Difficulty {
+    /// Multiply the difficulty attributes by the given multipliers while
+    /// keeping them within `0.0..=10.0`.
+    pub fn apply_mods(&mut self, cs_mult: f32, ar_mult: f32, od_mult: f32, hp_mult: f32) {
+        self.circle_size = (self.circle_size * cs_mult).clamp(0.0, 10.0);
+        self.approach_rate = (self.approach_rate * ar_mult).clamp(0.0, 10.0);
+        self.overall_difficulty = (self.overall_difficulty * od_mult).clamp(0.0, 10.0);
+        self.hp_drain_rate = (self.hp_drain_rate * hp_mult).clamp(0.0, 10.0);
+    }
+
+    /// Apply the Hard Rock mod i.e. multiply CS by 1.3 and AR, OD, and HP by
+    /// 1.4, all capped at 10.
+    pub fn apply_hard_rock(&mut self) {
+        self.apply_mods(1.3, 1.4, 1.4, 1.4);
+    }
+
+    /// Apply the Easy mod i.e. halve CS, AR, OD, and HP.
+    pub fn apply_easy(&mut self) {
+        self.apply_mods(0.5, 0.5, 0.5, 0.5);
+    }
+
+    /// The time in milliseconds that hit objects appear before their start
+    /// time.
+    ///
+    /// The approach rate is clamped to `0.0..=10.0`.
+    pub fn ar_to_preempt_ms(&self) -> f64 {
+        ar_to_preempt_ms(self.approach_rate)
+    }
+
+    /// Convert a preempt time in milliseconds back to an approach rate.
+    ///
+    /// The resulting approach rate is clamped to `0.0..=10.0`.
+    pub fn preempt_ms_to_ar(preempt: f64) -> f32 {
+        let ar = inverse_difficulty_range(preempt, PREEMPT_RANGE);
+
+        (ar as f32).clamp(0.0, 10.0)
+    }
+
+    /// The osu!standard hit window in milliseconds for a 300.
+    ///
+    /// The overall difficulty is clamped to `0.0..=10.0`.
+    pub fn od_to_great_ms(&self) -> f64 {
+        od_to_great_ms(self.overall_difficulty)
+    }
+
+    /// The osu!standard hit window in milliseconds for a 100.
+    ///
+    /// The overall difficulty is clamped to `0.0..=10.0`.
+    pub fn od_to_ok_ms(&self) -> f64 {
+        od_to_ok_ms(self.overall_difficulty)
+    }
+
+    /// The osu!standard hit window in milliseconds for a 50.
+    ///
+    /// The overall difficulty is clamped to `0.0..=10.0`.
+    pub fn od_to_meh_ms(&self) -> f64 {
+        od_to_meh_ms(self.overall_difficulty)
+    }
+
+    /// The osu!taiko hit window in milliseconds for a great hit.
+    ///
+    /// The overall difficulty is clamped to `0.0..=10.0`.
+    pub fn od_to_taiko_great_ms(&self) -> f64 {
+        od_to_taiko_great_ms(self.overall_difficulty)
+    }
+
+    /// The osu!taiko hit window in milliseconds for an ok hit.
+    ///
+    /// The overall difficulty is clamped to `0.0..=10.0`.
+    pub fn od_to_taiko_ok_ms(&self) -> f64 {
+        od_to_taiko_ok_ms(self.overall_difficulty)
+    }
+}
+
+/// Values at difficulty 0, 5, and 10.
+type DifficultyRange = (f64, f64, f64);
+
+const PREEMPT_RANGE: DifficultyRange = (1800.0, 1200.0, 450.0);
+const GREAT_RANGE: DifficultyRange = (80.0, 50.0, 20.0);
+const OK_RANGE: DifficultyRange = (140.0, 100.0, 60.0);
+const MEH_RANGE: DifficultyRange = (200.0, 150.0, 100.0);
+const TAIKO_GREAT_RANGE: DifficultyRange = (50.0, 35.0, 20.0);
+const TAIKO_OK_RANGE: DifficultyRange = (120.0, 80.0, 50.0);
+const SPINNER_RPS_RANGE: DifficultyRange = (3.0, 5.0, 7.5);
+
+pub(crate) fn ar_to_preempt_ms(ar: f32) -> f64 {
+    difficulty_range(ar, PREEMPT_RANGE)
+}
+
+pub(crate) fn od_to_great_ms(od: f32) -> f64 {
+    difficulty_range(od, GREAT_RANGE)
+}
+
+pub(crate) fn od_to_ok_ms(od: f32) -> f64 {
+    difficulty_range(od, OK_RANGE)
+}
+
+pub(crate) fn od_to_meh_ms(od: f32) -> f64 {
+    difficulty_range(od, MEH_RANGE)
+}
+
+pub(crate) fn od_to_taiko_great_ms(od: f32) -> f64 {
+    difficulty_range(od, TAIKO_GREAT_RANGE)
+}
+
+pub(crate) fn od_to_taiko_ok_ms(od: f32) -> f64 {
+    difficulty_range(od, TAIKO_OK_RANGE)
+}
+
+/// The minimum amount of rotations per second required to clear a spinner.
+pub(crate) fn od_to_spinner_rps(od: f32) -> f64 {
+    difficulty_range(od, SPINNER_RPS_RANGE)
+}
+
+fn difficulty_range(difficulty: f32, (min, mid, max): DifficultyRange) -> f64 {
+    let difficulty = f64::from(difficulty.clamp(0.0, 10.0));
+
+    if difficulty > 5.0 {
+        mid + (max - mid) * (difficulty - 5.0) / 5.0
+    } else if difficulty < 5.0 {
+        mid + (mid - min) * (difficulty - 5.0) / 5.0
+    } else {
+        mid
+    }
+}
+
+fn inverse_difficulty_range(value: f64, (min, mid, max): DifficultyRange) -> f64 {
+    if (value - mid).signum() == (max - mid).signum() {
+        (value - mid) / (max - mid) * 5.0 + 5.0
+    } else {
+        (value - mid) / (mid - min) * 5.0 + 5.0
+    }
+}
+
+/// Borrowed view on the `[Difficulty]` values of a [`Beatmap`].
+///
+/// Created through [`Beatmap::difficulty_view`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct DifficultyRef<'a> {
+    pub hp_drain_rate: &'a f32,
+    pub circle_size: &'a f32,
+    pub overall_difficulty: &'a f32,
+    pub approach_rate: &'a f32,
+    pub slider_multiplier: &'a f64,
+    pub slider_tick_rate: &'a f64,
+}
+
 impl From<Difficulty> for Beatmap {
     fn from(difficulty: Difficulty) -> Self {
         Self {
@@ -158,3 +306,73 @@ impl DecodeBeatmap for Difficulty {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hard_rock() {
+        let mut difficulty = Difficulty {
+            circle_size: 4.0,
+            approach_rate: 9.0,
+            overall_difficulty: 8.0,
+            hp_drain_rate: 6.0,
+            ..Default::default()
+        };
+
+        difficulty.apply_hard_rock();
+
+        assert!((difficulty.circle_size - 5.2).abs() < f32::EPSILON);
+        assert!((difficulty.approach_rate - 10.0).abs() < f32::EPSILON);
+        assert!((difficulty.overall_difficulty - 10.0).abs() < f32::EPSILON);
+        assert!((difficulty.hp_drain_rate - 8.4).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn easy() {
+        let mut difficulty = Difficulty {
+            circle_size: 4.0,
+            ..Default::default()
+        };
+
+        difficulty.apply_easy();
+
+        assert!((difficulty.circle_size - 2.0).abs() < f32::EPSILON);
+        assert!((difficulty.approach_rate - 2.5).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn preempt() {
+        let mut difficulty = Difficulty {
+            approach_rate: 5.0,
+            ..Default::default()
+        };
+
+        assert!((difficulty.ar_to_preempt_ms() - 1200.0).abs() < f64::EPSILON);
+
+        difficulty.approach_rate = 9.0;
+        assert!((difficulty.ar_to_preempt_ms() - 600.0).abs() < f64::EPSILON);
+
+        difficulty.approach_rate = 11.0;
+        assert!((difficulty.ar_to_preempt_ms() - 450.0).abs() < f64::EPSILON);
+
+        assert!((Difficulty::preempt_ms_to_ar(600.0) - 9.0).abs() < f32::EPSILON);
+        assert!((Difficulty::preempt_ms_to_ar(1560.0) - 2.0).abs() < f32::EPSILON);
+        assert!((Difficulty::preempt_ms_to_ar(300.0) - 10.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn hit_windows() {
+        let difficulty = Difficulty {
+            overall_difficulty: 8.0,
+            ..Default::default()
+        };
+
+        assert!((difficulty.od_to_great_ms() - 32.0).abs() < f64::EPSILON);
+        assert!((difficulty.od_to_ok_ms() - 76.0).abs() < f64::EPSILON);
+        assert!((difficulty.od_to_meh_ms() - 120.0).abs() < f64::EPSILON);
+        assert!((difficulty.od_to_taiko_great_ms() - 26.0).abs() < f64::EPSILON);
+        assert!((difficulty.od_to_taiko_ok_ms() - 62.0).abs() < f64::EPSILON);
+    }
+}
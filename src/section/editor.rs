@@ -6,6 +6,7 @@ use crate::{
 
 /// Struct containing all data from a `.osu` file's `[Editor]` section.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Editor {
     pub bookmarks: Vec<i32>,
     pub distance_spacing: f64,
@@ -84,11 +85,22 @@ impl DecodeBeatmap for Editor {
 
         match key {
             EditorKey::Bookmarks => {
+                // Invalid values are skipped and floats are truncated
                 state.bookmarks = value
                     .split(',')
-                    .map(str::parse)
-                    .filter_map(Result::ok)
+                    .map(str::trim)
+                    .filter_map(|bookmark| {
+                        bookmark.parse().ok().or_else(|| {
+                            bookmark
+                                .parse::<f64>()
+                                .ok()
+                                .filter(|bookmark| bookmark.is_finite())
+                                .map(|bookmark| bookmark as i32)
+                        })
+                    })
                     .collect();
+
+                state.bookmarks.sort_unstable();
             }
             EditorKey::DistanceSpacing => state.distance_spacing = value.parse_num()?,
             EditorKey::BeatDivisor => state.beat_divisor = value.parse_num()?,
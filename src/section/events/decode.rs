@@ -1,16 +1,20 @@
 use crate::{
     decode::{DecodeBeatmap, DecodeState},
+    section::colors::Color,
     util::{ParseNumber, ParseNumberError, StrExt},
     Beatmap,
 };
 
-use super::{BreakPeriod, EventType, ParseEventTypeError};
+use super::{BreakPeriod, EventType, ParseEventTypeError, StoryboardFileRef};
 
 /// Struct containing all data from a `.osu` file's `[Events]` section.
 #[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Events {
     pub background_file: String,
     pub breaks: Vec<BreakPeriod>,
+    pub background_colors: Vec<(f64, Color)>,
+    pub storyboard_files: Vec<StoryboardFileRef>,
 }
 
 impl From<Events> for Beatmap {
@@ -18,6 +22,8 @@ impl From<Events> for Beatmap {
         Self {
             background_file: events.background_file,
             breaks: events.breaks,
+            background_colors: events.background_colors,
+            storyboard_files: events.storyboard_files,
             ..Self::default()
         }
     }
@@ -75,13 +81,39 @@ impl DecodeBeatmap for Events {
         };
 
         match event_type.parse()? {
-            EventType::Sprite => {
-                if state.background_file.is_empty() {
-                    state.background_file = split
-                        .next()
-                        .ok_or(ParseEventsError::InvalidLine)?
-                        .clean_filename();
+            kind @ (EventType::Sprite | EventType::Animation) => {
+                let filename = split
+                    .next()
+                    .ok_or(ParseEventsError::InvalidLine)?
+                    .clean_filename();
+
+                if kind == EventType::Sprite && state.background_file.is_empty() {
+                    state.background_file.clone_from(&filename);
                 }
+
+                state.storyboard_files.push(StoryboardFileRef {
+                    kind,
+                    filename,
+                    layer: start_time.trim().to_owned(),
+                    origin: Some(event_params.trim().to_owned()),
+                    time: None,
+                    params: split.collect::<Vec<_>>().join(","),
+                });
+            }
+            EventType::Sample => {
+                let filename = split
+                    .next()
+                    .ok_or(ParseEventsError::InvalidLine)?
+                    .clean_filename();
+
+                state.storyboard_files.push(StoryboardFileRef {
+                    kind: EventType::Sample,
+                    filename,
+                    layer: event_params.trim().to_owned(),
+                    origin: None,
+                    time: Some(f64::parse(start_time)?),
+                    params: split.collect::<Vec<_>>().join(","),
+                });
             }
             EventType::Video => {
                 const VIDEO_EXTENSIONS: &[[u8; 3]] = &[
@@ -112,7 +144,28 @@ impl DecodeBeatmap for Events {
                     end_time,
                 });
             }
-            EventType::Color | EventType::Sample | EventType::Animation => {}
+            EventType::Color => {
+                let start_time = f64::parse(start_time)?;
+
+                let (Some(green), Some(blue)) = (split.next(), split.next()) else {
+                    return Err(ParseEventsError::InvalidLine);
+                };
+
+                let parse_channel = |s: &str| {
+                    s.trim()
+                        .parse::<u8>()
+                        .map_err(ParseNumberError::InvalidInteger)
+                };
+
+                let color = Color::new(
+                    parse_channel(event_params)?,
+                    parse_channel(green)?,
+                    parse_channel(blue)?,
+                    255,
+                );
+
+                state.background_colors.push((start_time, color));
+            }
         }
 
         Ok(())
@@ -8,6 +8,7 @@ pub(crate) mod decode; // pub(crate) for intradoc-links
 ///
 /// [`Beatmap`]: crate::beatmap::Beatmap
 #[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BreakPeriod {
     pub start_time: f64,
     pub end_time: f64,
@@ -28,8 +29,30 @@ impl BreakPeriod {
     }
 }
 
+/// A file referenced by a storyboard event of type [`EventType::Sprite`],
+/// [`EventType::Animation`], or [`EventType::Sample`].
+///
+/// Storyboard commands are not parsed.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StoryboardFileRef {
+    pub kind: EventType,
+    pub filename: String,
+    /// The layer, e.g. `Background` or `Foreground`. For samples, this is
+    /// the layer's index.
+    pub layer: String,
+    /// The origin, e.g. `Centre`. Always `None` for samples.
+    pub origin: Option<String>,
+    /// The start time. Only available for samples.
+    pub time: Option<f64>,
+    /// All remaining comma-separated values after the filename, e.g. the
+    /// position, as they appear in the file.
+    pub params: String,
+}
+
 /// The type of an event.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum EventType {
     Background,
     Video,
@@ -40,6 +63,21 @@ pub enum EventType {
     Animation,
 }
 
+impl EventType {
+    /// The name of the event type as it may appear in `.osu` files.
+    pub const fn to_str(self) -> &'static str {
+        match self {
+            Self::Background => "Background",
+            Self::Video => "Video",
+            Self::Break => "Break",
+            Self::Color => "Colour",
+            Self::Sprite => "Sprite",
+            Self::Sample => "Sample",
+            Self::Animation => "Animation",
+        }
+    }
+}
+
 impl FromStr for EventType {
     type Err = ParseEventTypeError;
 
@@ -9,6 +9,7 @@ use super::{CountdownType, GameMode, ParseCountdownTypeError, ParseGameModeError
 
 /// Struct containing all data from a `.osu` file's `[General]` section.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct General {
     pub audio_file: String,
     pub audio_lead_in: f64,
@@ -114,6 +115,27 @@ impl DecodeState for GeneralState {
     }
 }
 
+/// Parses the preview time either in milliseconds or as `MM:SS:ms` timestamp.
+fn parse_preview_time(value: &str) -> Result<i32, ParseNumberError> {
+    let mut split = value.split(':');
+
+    let (Some(minutes), Some(seconds), Some(millis), None) =
+        (split.next(), split.next(), split.next(), split.next())
+    else {
+        return i32::parse(value);
+    };
+
+    let minutes = i32::parse(minutes)?;
+    let seconds = i32::parse(seconds)?;
+    let millis = i32::parse(millis)?;
+
+    Ok(minutes
+        .saturating_mul(60)
+        .saturating_add(seconds)
+        .saturating_mul(1000)
+        .saturating_add(millis))
+}
+
 impl DecodeBeatmap for General {
     type Error = ParseGeneralError;
     type State = GeneralState;
@@ -126,7 +148,7 @@ impl DecodeBeatmap for General {
         match key {
             GeneralKey::AudioFilename => state.audio_file = value.to_standardized_path(),
             GeneralKey::AudioLeadIn => state.audio_lead_in = f64::from(i32::parse(value)?),
-            GeneralKey::PreviewTime => state.preview_time = i32::parse(value)?,
+            GeneralKey::PreviewTime => state.preview_time = parse_preview_time(value)?,
             GeneralKey::SampleSet => state.default_sample_bank = value.parse()?,
             GeneralKey::SampleVolume => state.default_sample_volume = value.parse_num()?,
             GeneralKey::StackLeniency => state.stack_leniency = value.parse_num()?,
@@ -6,6 +6,7 @@ pub(crate) mod decode; // pub(crate) for intradoc-links
 
 /// An osu! gamemode.
 #[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GameMode {
     #[default]
     Osu,
@@ -14,6 +15,46 @@ pub enum GameMode {
     Mania,
 }
 
+impl GameMode {
+    /// Whether the mode lays out its hit objects in columns.
+    pub const fn uses_columns(self) -> bool {
+        matches!(self, Self::Mania)
+    }
+
+    /// The default amount of keys for the mode, if it uses keys at all.
+    pub const fn default_key_count(self) -> Option<u8> {
+        match self {
+            Self::Mania => Some(4),
+            Self::Osu | Self::Taiko | Self::Catch => None,
+        }
+    }
+
+    /// Parse a [`GameMode`] from either its numeric value or a ruleset name.
+    ///
+    /// Accepted names are `"osu"`, `"taiko"`, `"fruits"`, `"catch"`, and
+    /// `"mania"`, case-insensitively. Decoding `.osu` files only accepts
+    /// numeric values through the [`FromStr`] implementation.
+    pub fn try_from_str(mode: &str) -> Result<Self, ParseGameModeError> {
+        if let Ok(mode) = mode.parse() {
+            return Ok(mode);
+        }
+
+        let names = [
+            ("osu", Self::Osu),
+            ("taiko", Self::Taiko),
+            ("fruits", Self::Catch),
+            ("catch", Self::Catch),
+            ("mania", Self::Mania),
+        ];
+
+        names
+            .into_iter()
+            .find(|(name, _)| mode.eq_ignore_ascii_case(name))
+            .map(|(_, mode)| mode)
+            .ok_or(ParseGameModeError)
+    }
+}
+
 thiserror! {
     #[error("invalid game mode")]
     /// Error when failing to parse a [`GameMode`].
@@ -51,6 +92,7 @@ impl From<u8> for GameMode {
 ///
 /// [`Beatmap`]: crate::beatmap::Beatmap
 #[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CountdownType {
     #[default]
     None,
@@ -79,3 +121,17 @@ thiserror! {
     #[derive(Debug)]
     pub struct ParseCountdownTypeError;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mode_columns() {
+        assert!(GameMode::Mania.uses_columns());
+        assert_eq!(GameMode::Mania.default_key_count(), Some(4));
+
+        assert!(!GameMode::Osu.uses_columns());
+        assert_eq!(GameMode::Osu.default_key_count(), None);
+    }
+}
@@ -1,11 +1,32 @@
 use crate::util::Pos;
 
+use super::{validate_combo_offset, InvalidComboOffsetError};
+
 /// A circle note [`HitObject`].
 ///
 /// [`HitObject`]: crate::section::hit_objects::HitObject
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HitObjectCircle {
     pub pos: Pos,
     pub new_combo: bool,
     pub combo_offset: i32,
+    /// The stack height as calculated by [`Beatmap::apply_stacking`].
+    ///
+    /// Zero until stacking is applied.
+    ///
+    /// [`Beatmap::apply_stacking`]: crate::Beatmap::apply_stacking
+    pub stack_height: i32,
+}
+
+impl HitObjectCircle {
+    /// Sets the combo offset i.e. the amount of combo colors to skip.
+    ///
+    /// Returns an error if the offset is not between 0 and 7 because it
+    /// would not fit into the hit object type when encoding.
+    pub fn set_combo_offset(&mut self, offset: i32) -> Result<(), InvalidComboOffsetError> {
+        self.combo_offset = validate_combo_offset(offset)?;
+
+        Ok(())
+    }
 }
@@ -1,10 +1,11 @@
 use std::{cmp, ptr, slice};
 
 use crate::{
-    decode::{DecodeBeatmap, DecodeState},
+    decode::{DecodeBeatmap, DecodeOptions, DecodeState},
     section::{
+        colors::Color,
         difficulty::{Difficulty, DifficultyState, ParseDifficultyError},
-        events::{BreakPeriod, Events, EventsState, ParseEventsError},
+        events::{BreakPeriod, Events, EventsState, ParseEventsError, StoryboardFileRef},
         general::{CountdownType, GameMode},
         hit_objects::{slider::path_type::PathType, CurveBuffers, BASE_SCORING_DIST},
         timing_points::{
@@ -27,6 +28,7 @@ use super::{
 /// Struct containing all data from a `.osu` file's `[HitObjects]`, `[Events]`,
 /// `[Difficulty]`, `[TimingPoints]` and `[General]` section.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HitObjects {
     // General
     pub audio_file: String,
@@ -55,6 +57,8 @@ pub struct HitObjects {
     // Events
     pub background_file: String,
     pub breaks: Vec<BreakPeriod>,
+    pub background_colors: Vec<(f64, Color)>,
+    pub storyboard_files: Vec<StoryboardFileRef>,
 
     // TimingPoints
     pub control_points: ControlPoints,
@@ -92,6 +96,8 @@ impl Default for HitObjects {
             slider_tick_rate: difficulty.slider_tick_rate,
             background_file: events.background_file,
             breaks: events.breaks,
+            background_colors: events.background_colors,
+            storyboard_files: events.storyboard_files,
             control_points: timing_points.control_points,
             hit_objects: Vec::default(),
         }
@@ -123,6 +129,8 @@ impl From<HitObjects> for Beatmap {
             slider_tick_rate: hit_objects.slider_tick_rate,
             background_file: hit_objects.background_file,
             breaks: hit_objects.breaks,
+            background_colors: hit_objects.background_colors,
+            storyboard_files: hit_objects.storyboard_files,
             control_points: hit_objects.control_points,
             hit_objects: hit_objects.hit_objects,
             ..Self::default()
@@ -167,6 +175,7 @@ pub struct HitObjectsState {
     pub difficulty: DifficultyState,
     pub hit_objects: Vec<HitObject>,
     point_split: Vec<*const str>,
+    dedup_hit_objects: bool,
 }
 
 impl HitObjectsState {
@@ -394,8 +403,41 @@ impl DecodeState for HitObjectsState {
             timing_points: TimingPointsState::create(version),
             difficulty: DifficultyState::create(version),
             hit_objects: Vec::new(),
+            dedup_hit_objects: false,
         }
     }
+
+    fn create_with_options(version: i32, options: &DecodeOptions) -> Self {
+        Self {
+            dedup_hit_objects: options.dedup_hit_objects,
+            ..Self::create(version)
+        }
+    }
+}
+
+/// Whether `curr` is identical to `prev` apart from its combo information.
+///
+/// The first hit object always starts a new combo so combo information would
+/// prevent an exact duplicate of it from being detected.
+fn is_duplicate(prev: &HitObject, curr: &HitObject) -> bool {
+    let same_kind = match (&prev.kind, &curr.kind) {
+        (HitObjectKind::Circle(a), HitObjectKind::Circle(b)) => a.pos == b.pos,
+        (HitObjectKind::Slider(a), HitObjectKind::Slider(b)) => {
+            a.pos == b.pos
+                && a.path == b.path
+                && a.repeat_count == b.repeat_count
+                && a.node_samples == b.node_samples
+        }
+        (HitObjectKind::Spinner(a), HitObjectKind::Spinner(b)) => {
+            a.pos == b.pos && a.duration.total_cmp(&b.duration).is_eq()
+        }
+        (HitObjectKind::Hold(a), HitObjectKind::Hold(b)) => {
+            a.pos_x.total_cmp(&b.pos_x).is_eq() && a.duration.total_cmp(&b.duration).is_eq()
+        }
+        _ => false,
+    };
+
+    same_kind && prev.start_time.total_cmp(&curr.start_time).is_eq() && prev.samples == curr.samples
 }
 
 pub(crate) fn get_precision_adjusted_beat_len(
@@ -421,6 +463,27 @@ pub(crate) fn get_precision_adjusted_beat_len(
     beat_len * bpm_multiplier
 }
 
+/// The velocity of a slider starting at the given time.
+pub(crate) fn slider_velocity_at(
+    time: f64,
+    control_points: &ControlPoints,
+    slider_multiplier: f64,
+    mode: GameMode,
+) -> f64 {
+    let beat_len = control_points
+        .timing_point_at(time)
+        .map_or(TimingPoint::DEFAULT_BEAT_LEN, |point| point.beat_len);
+
+    let slider_velocity = control_points
+        .difficulty_point_at(time)
+        .map_or(DifficultyPoint::DEFAULT_SLIDER_VELOCITY, |point| {
+            point.slider_velocity
+        });
+
+    f64::from(BASE_SCORING_DIST) * slider_multiplier
+        / get_precision_adjusted_beat_len(slider_velocity, beat_len, mode)
+}
+
 impl From<HitObjectsState> for HitObjects {
     fn from(state: HitObjectsState) -> Self {
         const CONTROL_POINT_LENIENCY: f64 = 5.0;
@@ -430,31 +493,26 @@ impl From<HitObjectsState> for HitObjects {
         let events = state.events;
 
         let mut hit_objects = state.hit_objects;
+
+        // Stable sort so that objects with equal start times keep their order
+        // of the file, just like osu! does.
         hit_objects.sort_by(|a, b| a.start_time.total_cmp(&b.start_time));
 
+        if state.dedup_hit_objects {
+            hit_objects.dedup_by(|curr, prev| is_duplicate(prev, curr));
+        }
+
         HitObjectsState::post_process_breaks(&mut hit_objects, &events);
         let mut bufs = CurveBuffers::default();
 
         for h in hit_objects.iter_mut() {
             if let HitObjectKind::Slider(ref mut slider) = h.kind {
-                let beat_len = timing_points
-                    .control_points
-                    .timing_point_at(h.start_time)
-                    .map_or(TimingPoint::DEFAULT_BEAT_LEN, |point| point.beat_len);
-
-                let slider_velocity = timing_points
-                    .control_points
-                    .difficulty_point_at(h.start_time)
-                    .map_or(DifficultyPoint::DEFAULT_SLIDER_VELOCITY, |point| {
-                        point.slider_velocity
-                    });
-
-                slider.velocity = f64::from(BASE_SCORING_DIST) * difficulty.slider_multiplier
-                    / get_precision_adjusted_beat_len(
-                        slider_velocity,
-                        beat_len,
-                        timing_points.mode,
-                    );
+                slider.velocity = slider_velocity_at(
+                    h.start_time,
+                    &timing_points.control_points,
+                    difficulty.slider_multiplier,
+                    timing_points.mode,
+                );
 
                 let span_count = f64::from(slider.span_count());
                 let duration = slider.duration_with_bufs(&mut bufs);
@@ -509,6 +567,8 @@ impl From<HitObjectsState> for HitObjects {
             slider_tick_rate: difficulty.slider_tick_rate,
             background_file: events.background_file,
             breaks: events.breaks,
+            background_colors: events.background_colors,
+            storyboard_files: events.storyboard_files,
             control_points: timing_points.control_points,
             hit_objects,
         }
@@ -594,6 +654,7 @@ impl DecodeBeatmap for HitObjects {
                 pos,
                 new_combo: state.first_object() || state.last_object_was_spinner() || new_combo,
                 combo_offset: if new_combo { combo_offset } else { 0 },
+                stack_height: 0,
             };
 
             HitObjectKind::Circle(circle)
@@ -666,6 +727,7 @@ impl DecodeBeatmap for HitObjects {
                 node_samples,
                 repeat_count,
                 velocity: 1.0,
+                stack_height: 0,
             };
 
             HitObjectKind::Slider(slider)
@@ -699,6 +761,16 @@ impl DecodeBeatmap for HitObjects {
                     .ok_or(ParseHitObjectsError::InvalidLine)?
                     .parse_num::<f64>()?;
 
+                // Not an error since the hold note is still usable, just
+                // with a duration of zero
+                #[cfg(feature = "tracing")]
+                if new_end_time < start_time {
+                    tracing::warn!(
+                        "Hold note ends at {new_end_time} before it starts at {start_time}; \
+                        clamping its duration to zero"
+                    );
+                }
+
                 end_time = start_time.max(new_end_time);
 
                 bank_info.read_custom_sample_banks(ss, false)?;
@@ -12,6 +12,10 @@ use crate::util::{ParseNumber, ParseNumberError, StrExt};
 ///
 /// [`HitObject`]: crate::section::hit_objects::HitObject
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+// `unsafe` is only used to create `NonZeroU32` values whose invariant is
+// checked on deserialization as well
+#[cfg_attr(feature = "serde", allow(clippy::unsafe_derive_deserialize))]
 pub struct HitSampleInfo {
     pub name: HitSampleInfoName,
     pub bank: SampleBank,
@@ -24,6 +28,7 @@ pub struct HitSampleInfo {
 
 /// The name of a [`HitSampleInfo`].
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum HitSampleInfoName {
     Default(HitSampleDefaultName),
     File(String),
@@ -31,6 +36,7 @@ pub enum HitSampleInfoName {
 
 /// The default names of a [`HitSampleInfo`].
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum HitSampleDefaultName {
     Normal,
     Whistle,
@@ -107,7 +113,8 @@ impl Display for LookupName<'_> {
 }
 
 /// The different types of samples.
-#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SampleBank {
     #[default]
     None,
@@ -188,10 +195,27 @@ impl HitSoundType {
     pub const FINISH: u8 = 4;
     pub const CLAP: u8 = 8;
 
+    /// Create a [`HitSoundType`] from its raw bitflags.
+    pub const fn from_bits(bits: u8) -> Self {
+        Self(bits)
+    }
+
+    /// The raw bitflags.
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
     /// Check whether any of the given bitflags are set.
     pub const fn has_flag(self, flag: u8) -> bool {
         (self.0 & flag) != 0
     }
+
+    /// Check whether any of the given bitflags are set.
+    ///
+    /// Alias for [`HitSoundType::has_flag`].
+    pub const fn contains(self, flag: u8) -> bool {
+        self.has_flag(flag)
+    }
 }
 
 impl From<&[HitSampleInfo]> for HitSoundType {
@@ -274,6 +298,31 @@ pub struct SampleBankInfo {
 }
 
 impl SampleBankInfo {
+    /// Create a new [`SampleBankInfo`] from its parts.
+    ///
+    /// Just like when decoding, [`SampleBank::None`] means the bank is not
+    /// specified and the addition bank falls back to the normal bank.
+    pub fn new(
+        normal: SampleBank,
+        addition: SampleBank,
+        custom_sample_bank: i32,
+        volume: i32,
+        filename: Option<String>,
+    ) -> Self {
+        let bank_for_normal = (normal != SampleBank::None).then_some(normal);
+        let bank_for_addition = (addition != SampleBank::None)
+            .then_some(addition)
+            .or(bank_for_normal);
+
+        Self {
+            filename,
+            bank_for_normal,
+            bank_for_addition,
+            volume: cmp::max(0, volume),
+            custom_sample_bank,
+        }
+    }
+
     /// Read and store custom sample banks.
     pub fn read_custom_sample_banks(
         &mut self,
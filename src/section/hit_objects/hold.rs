@@ -2,6 +2,7 @@
 ///
 /// [`HitObject`]: crate::section::hit_objects::HitObject
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HitObjectHold {
     pub pos_x: f32,
     pub duration: f64,
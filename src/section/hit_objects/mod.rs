@@ -4,7 +4,9 @@ use std::{
     str::FromStr,
 };
 
-use self::hit_samples::HitSampleInfo;
+use crate::util::Pos;
+
+use self::hit_samples::{HitSampleInfo, HitSoundType, SampleBank, SampleBankInfo};
 pub use self::{
     circle::HitObjectCircle,
     decode::{HitObjects, HitObjectsState, ParseHitObjectsError},
@@ -24,6 +26,7 @@ pub(crate) mod decode; // pub(crate) for intradoc-links
 mod hold;
 mod slider;
 mod spinner;
+pub(crate) mod stacking;
 
 /// Audio-related types.
 pub mod hit_samples;
@@ -34,6 +37,7 @@ pub(crate) const BASE_SCORING_DIST: f32 = 100.0;
 ///
 /// [`Beatmap`]: crate::beatmap::Beatmap
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HitObject {
     pub start_time: f64,
     pub kind: HitObjectKind,
@@ -41,6 +45,83 @@ pub struct HitObject {
 }
 
 impl HitObject {
+    /// The center of the playfield which is the position of spinners.
+    const PLAYFIELD_CENTER: Pos = Pos::new(256.0, 192.0);
+
+    /// Create a new circle [`HitObject`].
+    ///
+    /// The hit object has a single normal sample whose bank and volume are
+    /// taken from the active sample point.
+    pub fn circle(pos: Pos, start_time: f64) -> Self {
+        Self {
+            start_time,
+            kind: HitObjectKind::Circle(HitObjectCircle {
+                pos,
+                new_combo: false,
+                combo_offset: 0,
+                stack_height: 0,
+            }),
+            samples: Self::default_samples(),
+        }
+    }
+
+    /// Create a new slider [`HitObject`].
+    ///
+    /// The slider's head, repeats, and tail as well as the hit object itself
+    /// have a single normal sample whose bank and volume are taken from the
+    /// active sample point. The velocity is set to `1.0` and should be
+    /// adjusted according to the active difficulty point.
+    pub fn slider(pos: Pos, start_time: f64, path: SliderPath, repeat_count: i32) -> Self {
+        let node_count = repeat_count.max(0) as usize + 2;
+
+        Self {
+            start_time,
+            kind: HitObjectKind::Slider(HitObjectSlider {
+                pos,
+                new_combo: false,
+                combo_offset: 0,
+                path,
+                node_samples: vec![Self::default_samples(); node_count],
+                repeat_count,
+                velocity: 1.0,
+                stack_height: 0,
+            }),
+            samples: Self::default_samples(),
+        }
+    }
+
+    /// Create a new spinner [`HitObject`] in the center of the playfield.
+    ///
+    /// The hit object has a single normal sample whose bank and volume are
+    /// taken from the active sample point.
+    pub fn spinner(start_time: f64, duration: f64) -> Self {
+        Self {
+            start_time,
+            kind: HitObjectKind::Spinner(HitObjectSpinner {
+                pos: Self::PLAYFIELD_CENTER,
+                duration,
+                new_combo: false,
+            }),
+            samples: Self::default_samples(),
+        }
+    }
+
+    /// Create a new hold note [`HitObject`].
+    ///
+    /// The hit object has a single normal sample whose bank and volume are
+    /// taken from the active sample point.
+    pub fn hold(pos_x: f32, start_time: f64, duration: f64) -> Self {
+        Self {
+            start_time,
+            kind: HitObjectKind::Hold(HitObjectHold { pos_x, duration }),
+            samples: Self::default_samples(),
+        }
+    }
+
+    pub(crate) fn default_samples() -> Vec<HitSampleInfo> {
+        vec![HitSampleInfo::new(HitSampleInfo::HIT_NORMAL, None, 0, 0)]
+    }
+
     /// Whether the [`HitObject`] starts a new combo.
     pub const fn new_combo(&self) -> bool {
         self.kind.new_combo()
@@ -71,10 +152,73 @@ impl HitObject {
             HitObjectKind::Hold(ref h) => self.start_time + h.duration,
         }
     }
+
+    /// Returns the position at which the [`HitObject`] ends.
+    ///
+    /// Hold notes have no vertical position so `None` is returned for them.
+    ///
+    /// If the slider's curve has not yet been accessed, it needs to be
+    /// calculated first for which the given [`CurveBuffers`] are used.
+    pub fn end_pos(&mut self, bufs: &mut CurveBuffers) -> Option<Pos> {
+        match self.kind {
+            HitObjectKind::Circle(ref h) => Some(h.pos),
+            HitObjectKind::Slider(ref mut h) => Some(h.end_position(bufs)),
+            HitObjectKind::Spinner(ref h) => Some(h.pos),
+            HitObjectKind::Hold(_) => None,
+        }
+    }
+
+    /// The distance between the base positions of two [`HitObject`]s.
+    ///
+    /// For sliders, their head position is used. Hold notes have no vertical
+    /// position so they are considered to be at `(pos_x, 192)`.
+    pub fn spatial_distance(&self, other: &HitObject) -> f32 {
+        self.base_pos().distance(other.base_pos())
+    }
+
+    const fn base_pos(&self) -> Pos {
+        match self.kind {
+            HitObjectKind::Circle(ref h) => h.pos,
+            HitObjectKind::Slider(ref h) => h.pos,
+            HitObjectKind::Spinner(ref h) => h.pos,
+            HitObjectKind::Hold(ref h) => Pos::new(h.pos_x, 192.0),
+        }
+    }
+
+    /// Returns a clone of the [`HitObject`] whose start time is shifted by
+    /// `offset`.
+    ///
+    /// Durations are relative to the start time so they remain unchanged.
+    /// Since the geometry of a slider stays the same, its curve is kept as
+    /// well if it has already been calculated.
+    #[must_use]
+    pub fn clone_shifted(&self, offset: f64) -> Self {
+        Self {
+            start_time: self.start_time + offset,
+            ..self.clone()
+        }
+    }
+
+    /// Regenerate the [`HitObject`]'s samples for the given [`HitSoundType`].
+    ///
+    /// A [`SampleBank::None`] for `bank` falls back to the default bank, and
+    /// for `addition_bank` it falls back to `bank`, just like when decoding.
+    pub fn set_hit_sound(
+        &mut self,
+        sound: HitSoundType,
+        bank: SampleBank,
+        addition_bank: SampleBank,
+        volume: i32,
+    ) {
+        let bank_info = SampleBankInfo::new(bank, addition_bank, 0, volume, None);
+
+        self.samples = bank_info.convert_sound_type(sound);
+    }
 }
 
 /// Additional data for a [`HitObject`] depending on its type.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum HitObjectKind {
     Circle(HitObjectCircle),
     Slider(HitObjectSlider),
@@ -92,6 +236,17 @@ impl HitObjectKind {
             Self::Hold(_) => false,
         }
     }
+
+    /// The [`HitObjectType`] flag of the [`HitObjectKind`] without any combo
+    /// information.
+    pub const fn type_flag(&self) -> i32 {
+        match self {
+            Self::Circle(_) => HitObjectType::CIRCLE,
+            Self::Slider(_) => HitObjectType::SLIDER,
+            Self::Spinner(_) => HitObjectType::SPINNER,
+            Self::Hold(_) => HitObjectType::HOLD,
+        }
+    }
 }
 
 /// The type of a [`HitObject`].
@@ -123,8 +278,6 @@ impl From<&HitObject> for HitObjectType {
                 if h.new_combo {
                     kind |= Self::NEW_COMBO;
                 }
-
-                kind |= Self::CIRCLE;
             }
             HitObjectKind::Slider(ref h) => {
                 kind |= h.combo_offset << 4;
@@ -132,20 +285,16 @@ impl From<&HitObject> for HitObjectType {
                 if h.new_combo {
                     kind |= Self::NEW_COMBO;
                 }
-
-                kind |= Self::SLIDER;
             }
             HitObjectKind::Spinner(ref h) => {
                 if h.new_combo {
                     kind |= Self::NEW_COMBO;
                 }
-
-                kind |= Self::SPINNER;
             }
-            HitObjectKind::Hold(_) => kind |= Self::HOLD,
+            HitObjectKind::Hold(_) => {}
         }
 
-        Self(kind)
+        Self(kind | hit_object.kind.type_flag())
     }
 }
 
@@ -164,6 +313,27 @@ thiserror! {
     pub struct ParseHitObjectTypeError(ParseIntError);
 }
 
+/// The maximum combo offset that fits into the bits of
+/// [`HitObjectType::COMBO_OFFSET`].
+const MAX_COMBO_OFFSET: i32 = HitObjectType::COMBO_OFFSET >> 4;
+
+thiserror! {
+    #[error("combo offset must be between 0 and 7")]
+    /// Error when setting an out-of-range combo offset.
+    #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+    pub struct InvalidComboOffsetError;
+}
+
+/// Validates that `offset` fits into the bits of
+/// [`HitObjectType::COMBO_OFFSET`].
+const fn validate_combo_offset(offset: i32) -> Result<i32, InvalidComboOffsetError> {
+    if 0 <= offset && offset <= MAX_COMBO_OFFSET {
+        Ok(offset)
+    } else {
+        Err(InvalidComboOffsetError)
+    }
+}
+
 impl From<HitObjectType> for i32 {
     fn from(kind: HitObjectType) -> Self {
         kind.0
@@ -183,3 +353,111 @@ impl BitAndAssign<i32> for HitObjectType {
         self.0 &= rhs;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::section::general::GameMode;
+
+    use super::*;
+
+    #[test]
+    fn clone_shifted_slider() {
+        let control_points = vec![
+            PathControlPoint::new(Pos::new(0.0, 0.0)),
+            PathControlPoint::new(Pos::new(100.0, 0.0)),
+        ];
+
+        let slider = HitObjectSlider {
+            pos: Pos::new(0.0, 0.0),
+            new_combo: false,
+            combo_offset: 0,
+            path: SliderPath::new(GameMode::Osu, control_points, None),
+            node_samples: Vec::new(),
+            repeat_count: 0,
+            velocity: 1.0,
+            stack_height: 0,
+        };
+
+        let mut h = HitObject {
+            start_time: 1000.0,
+            kind: HitObjectKind::Slider(slider),
+            samples: Vec::new(),
+        };
+
+        let mut shifted = h.clone_shifted(500.0);
+
+        assert!((shifted.start_time - 1500.0).abs() < f64::EPSILON);
+        assert!((shifted.end_time() - h.end_time() - 500.0).abs() < f64::EPSILON);
+        assert_eq!(shifted.kind, h.kind);
+    }
+
+    #[test]
+    fn set_hit_sound() {
+        let mut h = HitObject {
+            start_time: 1000.0,
+            kind: HitObjectKind::Circle(HitObjectCircle {
+                pos: Pos::new(0.0, 0.0),
+                new_combo: false,
+                combo_offset: 0,
+                stack_height: 0,
+            }),
+            samples: Vec::new(),
+        };
+
+        let sound = HitSoundType::from(HitSoundType::WHISTLE | HitSoundType::CLAP);
+        h.set_hit_sound(sound, SampleBank::Soft, SampleBank::Drum, 70);
+
+        let samples: Vec<_> = h
+            .samples
+            .iter()
+            .map(|sample| (&sample.name, sample.bank, sample.volume))
+            .collect();
+
+        assert_eq!(
+            samples,
+            [
+                (&HitSampleInfo::HIT_NORMAL, SampleBank::Soft, 70),
+                (&HitSampleInfo::HIT_WHISTLE, SampleBank::Drum, 70),
+                (&HitSampleInfo::HIT_CLAP, SampleBank::Drum, 70),
+            ]
+        );
+        assert!(h.samples[0].is_layered);
+    }
+
+    #[test]
+    fn kind_type_flags() {
+        let circle = HitObjectKind::Circle(HitObjectCircle {
+            pos: Pos::new(0.0, 0.0),
+            new_combo: true,
+            combo_offset: 2,
+            stack_height: 0,
+        });
+
+        let slider = HitObjectKind::Slider(HitObjectSlider {
+            pos: Pos::new(0.0, 0.0),
+            new_combo: true,
+            combo_offset: 0,
+            path: SliderPath::new(GameMode::Osu, Vec::new(), None),
+            node_samples: Vec::new(),
+            repeat_count: 0,
+            velocity: 1.0,
+            stack_height: 0,
+        });
+
+        let spinner = HitObjectKind::Spinner(HitObjectSpinner {
+            pos: Pos::new(0.0, 0.0),
+            duration: 1000.0,
+            new_combo: true,
+        });
+
+        let hold = HitObjectKind::Hold(HitObjectHold {
+            pos_x: 0.0,
+            duration: 1000.0,
+        });
+
+        assert_eq!(circle.type_flag(), HitObjectType::CIRCLE);
+        assert_eq!(slider.type_flag(), HitObjectType::SLIDER);
+        assert_eq!(spinner.type_flag(), HitObjectType::SPINNER);
+        assert_eq!(hold.type_flag(), HitObjectType::HOLD);
+    }
+}
@@ -15,6 +15,63 @@ pub struct CurveBuffers {
     lengths: Vec<f64>,
     vertices: Vec<Pos>,
     bezier: BezierBuffers,
+    dedup_epsilon: Option<f32>,
+    bezier_tolerance: Option<f32>,
+    circular_arc_tolerance: Option<f32>,
+}
+
+impl CurveBuffers {
+    /// Consecutive path points whose distance is at most `epsilon` will be
+    /// deduplicated when calculating a curve.
+    ///
+    /// Defaults to `None` in which case only exactly-equal points at the
+    /// joints of segments are deduplicated, just like osu! does.
+    pub const fn set_dedup_epsilon(&mut self, epsilon: Option<f32>) {
+        self.dedup_epsilon = epsilon;
+    }
+
+    /// The epsilon within which consecutive path points are deduplicated.
+    ///
+    /// See [`CurveBuffers::set_dedup_epsilon`].
+    pub const fn dedup_epsilon(&self) -> Option<f32> {
+        self.dedup_epsilon
+    }
+
+    /// Tolerance used to approximate bezier and B-spline segments. Smaller
+    /// values produce more detailed paths.
+    ///
+    /// Defaults to `None` in which case osu!'s tolerance of `0.25` is used.
+    pub const fn set_bezier_tolerance(&mut self, tolerance: Option<f32>) {
+        self.bezier_tolerance = tolerance;
+    }
+
+    /// The tolerance used to approximate bezier and B-spline segments.
+    ///
+    /// See [`CurveBuffers::set_bezier_tolerance`].
+    pub const fn bezier_tolerance(&self) -> Option<f32> {
+        self.bezier_tolerance
+    }
+
+    /// Tolerance used to approximate perfect curve segments. Smaller values
+    /// produce more detailed paths.
+    ///
+    /// Defaults to `None` in which case osu!'s tolerance of `0.1` is used.
+    pub const fn set_circular_arc_tolerance(&mut self, tolerance: Option<f32>) {
+        self.circular_arc_tolerance = tolerance;
+    }
+
+    /// The tolerance used to approximate perfect curve segments.
+    ///
+    /// See [`CurveBuffers::set_circular_arc_tolerance`].
+    pub const fn circular_arc_tolerance(&self) -> Option<f32> {
+        self.circular_arc_tolerance
+    }
+}
+
+#[derive(Copy, Clone)]
+struct Tolerances {
+    bezier: f32,
+    circular_arc: f32,
 }
 
 #[derive(Clone, Debug, Default)]
@@ -36,14 +93,13 @@ impl BezierBuffers {
 
         let additional = len - self.left.len();
 
-        self.left
-            .extend(iter::repeat(Pos::default()).take(additional));
+        self.left.extend(iter::repeat_n(Pos::default(), additional));
         self.right
-            .extend(iter::repeat(Pos::default()).take(additional));
+            .extend(iter::repeat_n(Pos::default(), additional));
         self.midpoints
-            .extend(iter::repeat(Pos::default()).take(additional));
+            .extend(iter::repeat_n(Pos::default(), additional));
         self.left_child
-            .extend(iter::repeat(Pos::default()).take(additional));
+            .extend(iter::repeat_n(Pos::default(), additional));
     }
 }
 
@@ -261,9 +317,17 @@ fn calculate_path(
         vertices,
         bezier,
         path,
+        dedup_epsilon,
+        bezier_tolerance,
+        circular_arc_tolerance,
         ..
     } = bufs;
 
+    let tolerances = Tolerances {
+        bezier: bezier_tolerance.unwrap_or(BEZIER_TOLERANCE),
+        circular_arc: circular_arc_tolerance.unwrap_or(CIRCULAR_ARC_TOLERANCE),
+    };
+
     path.clear();
     *optimized_len = 0.0;
 
@@ -304,13 +368,14 @@ fn calculate_path(
                     segment_kind,
                     optimized_len,
                     bezier,
+                    tolerances,
                 );
 
                 // * Skip the first vertex if it is the same as the last vertex from the previous segment
                 let skip_first = path_len
                     .checked_sub(1)
                     .zip(path.get(path_len))
-                    .map_or(false, |(idx, first)| &path[idx] == first);
+                    .is_some_and(|(idx, first)| &path[idx] == first);
 
                 if skip_first {
                     path[path_len..].rotate_left(1);
@@ -324,6 +389,10 @@ fn calculate_path(
         // * Start the new segment at the current vertex
         start = i;
     }
+
+    if let Some(epsilon) = *dedup_epsilon {
+        path.dedup_by(|curr, prev| prev.distance(*curr) <= epsilon);
+    }
 }
 
 fn calculate_length(bufs: &mut CurveBuffers, expected_len: Option<f64>, optimized_len: f64) {
@@ -402,17 +471,18 @@ fn calculate_subpath(
     path_type: SplineType,
     optimized_len: &mut f64,
     bufs: &mut BezierBuffers,
+    tolerances: Tolerances,
 ) {
     match path_type {
         SplineType::Linear => approximate_linear(path, sub_points),
         SplineType::PerfectCurve => {
             if let [a, b, c] = sub_points {
-                if approximate_circular_arc(path, *a, *b, *c) {
+                if approximate_circular_arc(path, *a, *b, *c, tolerances.circular_arc) {
                     return;
                 }
             }
 
-            approximate_bezier(path, sub_points, bufs);
+            approximate_bezier(path, sub_points, bufs, tolerances.bezier);
         }
         SplineType::Catmull => {
             let start_len = path.len();
@@ -455,14 +525,19 @@ fn calculate_subpath(
                 }
             }
         }
-        SplineType::BSpline => approximate_bezier(path, sub_points, bufs),
+        SplineType::BSpline => approximate_bezier(path, sub_points, bufs, tolerances.bezier),
     }
 }
 
-fn approximate_bezier(path: &mut Vec<Pos>, points: &[Pos], bufs: &mut BezierBuffers) {
+fn approximate_bezier(
+    path: &mut Vec<Pos>,
+    points: &[Pos],
+    bufs: &mut BezierBuffers,
+    tolerance: f32,
+) {
     bufs.extend_exact(points.len());
 
-    approximate_bspline(path, points, bufs);
+    approximate_bspline(path, points, bufs, tolerance);
 }
 
 fn approximate_catmull(path: &mut Vec<Pos>, points: &[Pos]) {
@@ -493,7 +568,7 @@ fn approximate_linear(path: &mut Vec<Pos>, points: &[Pos]) {
     path.extend(points);
 }
 
-fn approximate_circular_arc(path: &mut Vec<Pos>, a: Pos, b: Pos, c: Pos) -> bool {
+fn approximate_circular_arc(path: &mut Vec<Pos>, a: Pos, b: Pos, c: Pos, tolerance: f32) -> bool {
     let Some(pr) = circular_arc_properties(a, b, c) else {
         return false;
     };
@@ -503,10 +578,10 @@ fn approximate_circular_arc(path: &mut Vec<Pos>, a: Pos, b: Pos, c: Pos) -> bool
     // * is: 2 * Math.Acos(1 - TOLERANCE / r)
     // * The special case is required for extremely short sliders where the radius is smaller than
     // * the tolerance. This is a pathological rather than a realistic case.
-    let sub_points = if 2.0 * pr.radius <= CIRCULAR_ARC_TOLERANCE {
+    let sub_points = if 2.0 * pr.radius <= tolerance {
         2
     } else {
-        let divisor = 2.0 * (1.0 - (CIRCULAR_ARC_TOLERANCE / pr.radius)).acos();
+        let divisor = 2.0 * (1.0 - (tolerance / pr.radius)).acos();
 
         // In C# it holds `(int)Infinity == -2147483648` whereas in Rust it's 2147483647
         // so we need to workaround this edge case, see map /b/2568364
@@ -545,7 +620,12 @@ fn approximate_circular_arc(path: &mut Vec<Pos>, a: Pos, b: Pos, c: Pos) -> bool
     true
 }
 
-fn approximate_bspline(path: &mut Vec<Pos>, points: &[Pos], bufs: &mut BezierBuffers) {
+fn approximate_bspline(
+    path: &mut Vec<Pos>,
+    points: &[Pos],
+    bufs: &mut BezierBuffers,
+    tolerance: f32,
+) {
     let p = points.len();
 
     let mut to_flatten = Vec::new();
@@ -569,7 +649,7 @@ fn approximate_bspline(path: &mut Vec<Pos>, points: &[Pos], bufs: &mut BezierBuf
     } = bufs;
 
     while let Some(mut parent) = to_flatten.pop() {
-        if bezier_is_flat_enough(&parent) {
+        if bezier_is_flat_enough(&parent, tolerance) {
             // * If the control points we currently operate on are sufficiently "flat", we use
             // * an extension to De Casteljau's algorithm to obtain a piecewise-linear approximation
             // * of the bezier curve represented by our control points, consisting of the same amount
@@ -598,8 +678,8 @@ fn approximate_bspline(path: &mut Vec<Pos>, points: &[Pos], bufs: &mut BezierBuf
     path.push(points[p - 1]);
 }
 
-fn bezier_is_flat_enough(points: &[Pos]) -> bool {
-    let limit = BEZIER_TOLERANCE * BEZIER_TOLERANCE * 4.0;
+fn bezier_is_flat_enough(points: &[Pos], tolerance: f32) -> bool {
+    let limit = tolerance * tolerance * 4.0;
 
     !points
         .iter()
@@ -747,3 +827,34 @@ fn circular_arc_properties(a: Pos, b: Pos, c: Pos) -> Option<CircularArcProperti
         centre,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::section::hit_objects::PathType;
+
+    use super::*;
+
+    #[test]
+    fn dedup_epsilon() {
+        let mut points: Vec<_> = (0..20)
+            .map(|i| PathControlPoint::new(Pos::new(i as f32 * 0.01, 0.0)))
+            .collect();
+
+        points.push(PathControlPoint::new(Pos::new(100.0, 100.0)));
+        points[0].path_type = Some(PathType::BEZIER);
+
+        let mut bufs = CurveBuffers::default();
+        let exact_len = Curve::new(GameMode::Osu, &points, None, &mut bufs)
+            .path()
+            .len();
+
+        bufs.set_dedup_epsilon(Some(0.5));
+        let curve = Curve::new(GameMode::Osu, &points, None, &mut bufs);
+
+        assert!(curve.path().len() < exact_len);
+        assert!(curve
+            .path()
+            .windows(2)
+            .all(|window| window[0].distance(window[1]) > 0.5));
+    }
+}
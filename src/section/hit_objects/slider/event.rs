@@ -1,3 +1,5 @@
+use super::HitObjectSlider;
+
 /// The type of a [`SliderEvent`].
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum SliderEventType {
@@ -8,6 +10,36 @@ pub enum SliderEventType {
     Tail,
 }
 
+impl SliderEventType {
+    /// Whether the type is [`SliderEventType::Head`].
+    pub const fn is_head(self) -> bool {
+        matches!(self, Self::Head)
+    }
+
+    /// Whether the type is [`SliderEventType::Tick`].
+    ///
+    /// The legacy last tick is not considered a tick, see
+    /// [`SliderEventType::is_last_tick`].
+    pub const fn is_tick(self) -> bool {
+        matches!(self, Self::Tick)
+    }
+
+    /// Whether the type is [`SliderEventType::Repeat`].
+    pub const fn is_repeat(self) -> bool {
+        matches!(self, Self::Repeat)
+    }
+
+    /// Whether the type is [`SliderEventType::LastTick`].
+    pub const fn is_last_tick(self) -> bool {
+        matches!(self, Self::LastTick)
+    }
+
+    /// Whether the type is [`SliderEventType::Tail`].
+    pub const fn is_tail(self) -> bool {
+        matches!(self, Self::Tail)
+    }
+}
+
 /// A [`HitObjectSlider`] event as generated by [`SliderEventsIter`].
 ///
 /// [`HitObjectSlider`]: crate::section::hit_objects::HitObjectSlider
@@ -37,7 +69,7 @@ pub struct SliderEventsIter<'ticks_buf> {
 
 impl<'ticks_buf> SliderEventsIter<'ticks_buf> {
     const MAX_LEN: f64 = 100_000.0;
-    const TAIL_LENIENCY: f64 = -36.0;
+    const TAIL_LENIENCY: f64 = -HitObjectSlider::LEGACY_LAST_TICK_OFFSET;
 
     /// Create a new [`SliderEventsIter`].
     ///
@@ -1,8 +1,16 @@
-use crate::util::Pos;
+use crate::{
+    section::timing_points::{ControlPoints, DifficultyPoint, TimingPoint},
+    util::Pos,
+};
 
-use self::path::{PathControlPoint, SliderPath};
+use self::{
+    event::{SliderEvent, SliderEventsIter},
+    path::{PathControlPoint, SliderPath},
+};
 
-use super::{hit_samples::HitSampleInfo, CurveBuffers};
+use super::{
+    hit_samples::HitSampleInfo, validate_combo_offset, CurveBuffers, InvalidComboOffsetError,
+};
 
 pub mod curve;
 pub mod event;
@@ -13,6 +21,7 @@ pub mod path_type;
 ///
 /// [`HitObject`]: crate::section::hit_objects::HitObject
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HitObjectSlider {
     pub pos: Pos,
     pub new_combo: bool,
@@ -21,9 +30,29 @@ pub struct HitObjectSlider {
     pub node_samples: Vec<Vec<HitSampleInfo>>,
     pub repeat_count: i32,
     pub velocity: f64,
+    /// The stack height as calculated by [`Beatmap::apply_stacking`].
+    ///
+    /// Zero until stacking is applied.
+    ///
+    /// [`Beatmap::apply_stacking`]: crate::Beatmap::apply_stacking
+    pub stack_height: i32,
 }
 
 impl HitObjectSlider {
+    /// The offset in milliseconds before the slider end at which the legacy
+    /// last tick is placed.
+    pub const LEGACY_LAST_TICK_OFFSET: f64 = 36.0;
+
+    /// Sets the combo offset i.e. the amount of combo colors to skip.
+    ///
+    /// Returns an error if the offset is not between 0 and 7 because it
+    /// would not fit into the hit object type when encoding.
+    pub fn set_combo_offset(&mut self, offset: i32) -> Result<(), InvalidComboOffsetError> {
+        self.combo_offset = validate_combo_offset(offset)?;
+
+        Ok(())
+    }
+
     pub const fn span_count(&self) -> i32 {
         self.repeat_count + 1
     }
@@ -48,4 +77,176 @@ impl HitObjectSlider {
     pub fn duration_with_bufs(&mut self, bufs: &mut CurveBuffers) -> f64 {
         f64::from(self.span_count()) * self.path.curve_with_bufs(bufs).dist() / self.velocity
     }
+
+    /// Returns the time of each repeat i.e. each boundary between two spans.
+    ///
+    /// `start_time` is the start time of the slider's [`HitObject`].
+    ///
+    /// [`HitObject`]: crate::section::hit_objects::HitObject
+    pub fn repeat_times(&mut self, start_time: f64, bufs: &mut CurveBuffers) -> Vec<f64> {
+        let span_duration = self.duration_with_bufs(bufs) / f64::from(self.span_count());
+
+        (1..=self.repeat_count)
+            .map(|i| start_time + f64::from(i) * span_duration)
+            .collect()
+    }
+
+    /// Returns the time of the legacy last tick.
+    ///
+    /// The tick is placed [`LEGACY_LAST_TICK_OFFSET`] milliseconds before
+    /// the slider end but not before the slider's halfway point. This
+    /// matches the time of the [`SliderEventType::LastTick`] event.
+    ///
+    /// `start_time` is the start time of the slider's [`HitObject`].
+    ///
+    /// [`LEGACY_LAST_TICK_OFFSET`]: Self::LEGACY_LAST_TICK_OFFSET
+    /// [`SliderEventType::LastTick`]: event::SliderEventType::LastTick
+    /// [`HitObject`]: crate::section::hit_objects::HitObject
+    pub fn legacy_last_tick_time(&mut self, start_time: f64, bufs: &mut CurveBuffers) -> f64 {
+        let duration = self.duration_with_bufs(bufs);
+
+        (start_time + duration / 2.0).max(start_time + duration - Self::LEGACY_LAST_TICK_OFFSET)
+    }
+
+    /// Returns the position at which the slider ends.
+    ///
+    /// Sliders with an odd amount of spans end at their tail, otherwise at
+    /// their head. If the slider's curve has not yet been accessed, it needs
+    /// to be calculated first for which the given [`CurveBuffers`] are used.
+    pub fn end_position(&mut self, bufs: &mut CurveBuffers) -> Pos {
+        if self.span_count() % 2 == 0 {
+            return self.pos;
+        }
+
+        self.pos + self.path.curve_with_bufs(bufs).position_at(1.0)
+    }
+
+    /// Returns the path points of the slider, calculated with the
+    /// tolerances configured on the given [`CurveBuffers`].
+    ///
+    /// The stored curve of the slider is neither used nor modified so it
+    /// keeps its default fidelity. Positions are relative to the slider's
+    /// position.
+    pub fn render_path(&self, bufs: &mut CurveBuffers) -> Vec<Pos> {
+        self.path.uncached_curve(bufs).path().to_vec()
+    }
+
+    /// Returns an iterator over the head, tick, repeat, legacy last tick,
+    /// and tail events of the slider.
+    ///
+    /// `start_time` is the start time of the slider's [`HitObject`] and
+    /// `tick_dist` is the distance between ticks, usually the slider's
+    /// scoring distance, i.e. its velocity times the beat length, divided by
+    /// the beatmap's slider tick rate.
+    ///
+    /// The `ticks` buffer is used to store pending ticks internally so that
+    /// it can be re-used for multiple sliders.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rosu_map::section::hit_objects::{
+    ///     CurveBuffers, HitObjectKind, HitObjects, SliderEventType,
+    /// };
+    ///
+    /// let content = "osu file format v14
+    ///
+    /// [Difficulty]
+    /// SliderMultiplier:1
+    ///
+    /// [TimingPoints]
+    /// 0,1000,4,1,0,100,1,0
+    ///
+    /// [HitObjects]
+    /// 0,192,1000,2,0,L|200:192,2,200";
+    ///
+    /// let mut hit_objects = rosu_map::from_str::<HitObjects>(content).unwrap().hit_objects;
+    /// let h = &mut hit_objects[0];
+    ///
+    /// let HitObjectKind::Slider(ref mut slider) = h.kind else { unreachable!() };
+    ///
+    /// let tick_dist = slider.velocity * 1000.0;
+    /// let mut bufs = CurveBuffers::default();
+    /// let mut ticks = Vec::new();
+    ///
+    /// let kinds: Vec<_> = slider
+    ///     .events(h.start_time, tick_dist, &mut bufs, &mut ticks)
+    ///     .map(|event| event.kind)
+    ///     .collect();
+    ///
+    /// assert_eq!(
+    ///     kinds,
+    ///     [
+    ///         SliderEventType::Head,
+    ///         SliderEventType::Tick,
+    ///         SliderEventType::Repeat,
+    ///         SliderEventType::Tick,
+    ///         SliderEventType::LastTick,
+    ///         SliderEventType::Tail,
+    ///     ]
+    /// );
+    /// ```
+    ///
+    /// [`HitObject`]: crate::section::hit_objects::HitObject
+    pub fn events<'ticks>(
+        &mut self,
+        start_time: f64,
+        tick_dist: f64,
+        bufs: &mut CurveBuffers,
+        ticks: &'ticks mut Vec<SliderEvent>,
+    ) -> SliderEventsIter<'ticks> {
+        let dist = self.path.curve_with_bufs(bufs).dist();
+        let span_count = self.span_count();
+        let span_duration = self.duration_with_bufs(bufs) / f64::from(span_count);
+
+        SliderEventsIter::new(
+            start_time,
+            span_duration,
+            self.velocity,
+            tick_dist,
+            dist,
+            span_count,
+            ticks,
+        )
+    }
+
+    /// Returns the events of the slider as osu!standard generates them.
+    pub(crate) fn osu_events<'ticks>(
+        &mut self,
+        start_time: f64,
+        format_version: i32,
+        slider_tick_rate: f64,
+        control_points: &ControlPoints,
+        bufs: &mut CurveBuffers,
+        ticks: &'ticks mut Vec<SliderEvent>,
+    ) -> SliderEventsIter<'ticks> {
+        let beat_len = control_points
+            .timing_point_at(start_time)
+            .map_or(TimingPoint::DEFAULT_BEAT_LEN, |point| point.beat_len);
+
+        let (slider_velocity, generate_ticks) =
+            control_points.difficulty_point_at(start_time).map_or(
+                (
+                    DifficultyPoint::DEFAULT_SLIDER_VELOCITY,
+                    DifficultyPoint::DEFAULT_GENERATE_TICKS,
+                ),
+                |point| (point.slider_velocity, point.generate_ticks),
+            );
+
+        let tick_dist_multiplier = if format_version < 8 {
+            slider_velocity.recip()
+        } else {
+            1.0
+        };
+
+        let scoring_dist = self.velocity * beat_len;
+
+        let tick_dist = if generate_ticks {
+            scoring_dist / slider_tick_rate * tick_dist_multiplier
+        } else {
+            f64::INFINITY
+        };
+
+        self.events(start_time, tick_dist, bufs, ticks)
+    }
 }
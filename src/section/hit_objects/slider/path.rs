@@ -9,10 +9,12 @@ use super::{
 ///
 /// [`HitObjectSlider`]: crate::section::hit_objects::HitObjectSlider
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SliderPath {
     mode: GameMode,
     control_points: Vec<PathControlPoint>,
     expected_dist: Option<f64>,
+    #[cfg_attr(feature = "serde", serde(skip))]
     curve: Option<Curve>,
 }
 
@@ -84,6 +86,26 @@ impl SliderPath {
         }
     }
 
+    /// The distance of the [`Curve`].
+    ///
+    /// If the curve has not yet been accessed, it needs to be calculated
+    /// first. See [`SliderPath::curve`].
+    pub fn dist(&mut self) -> f64 {
+        self.curve().dist()
+    }
+
+    /// The expected distance if one was specified, otherwise the distance of
+    /// the [`Curve`].
+    ///
+    /// If the curve is needed but has not yet been accessed, its calculation
+    /// uses the given [`CurveBuffers`].
+    pub fn expected_or_curve_dist(&mut self, bufs: &mut CurveBuffers) -> f64 {
+        match self.expected_dist {
+            Some(dist) => dist,
+            None => self.curve_with_bufs(bufs).dist(),
+        }
+    }
+
     /// Returns a [`BorrowedCurve`].
     ///
     /// If the curve has been calculated before, the returned curve will borrow
@@ -100,6 +122,15 @@ impl SliderPath {
         }
     }
 
+    /// Calculates the curve with the given [`CurveBuffers`] without using or
+    /// storing the cached curve.
+    ///
+    /// This is useful when the buffers are configured differently than the
+    /// ones used for the stored curve, e.g. with a lower tolerance.
+    pub fn uncached_curve<'a>(&self, bufs: &'a mut CurveBuffers) -> BorrowedCurve<'a> {
+        BorrowedCurve::new(self.mode, &self.control_points, self.expected_dist, bufs)
+    }
+
     /// Returns a mutable reference to the control points.
     ///
     /// Note that calling this method will invalidate the stored curve
@@ -143,6 +174,7 @@ impl PartialEq for SliderPath {
 
 /// A positional control point of a curve.
 #[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PathControlPoint {
     pub pos: Pos,
     pub path_type: Option<PathType>,
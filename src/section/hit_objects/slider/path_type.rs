@@ -4,6 +4,7 @@ use std::num::NonZeroI32;
 ///
 /// [`SliderPath`]: crate::section::hit_objects::SliderPath
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PathType {
     pub kind: SplineType,
     pub degree: Option<NonZeroI32>,
@@ -28,6 +29,14 @@ impl PathType {
         }
     }
 
+    /// The degree of the [`PathType`] as plain integer, if any.
+    pub const fn degree(&self) -> Option<i32> {
+        match self.degree {
+            Some(degree) => Some(degree.get()),
+            None => None,
+        }
+    }
+
     /// Parse a string into a [`PathType`].
     ///
     /// The string should be of the form `"B<optional integer>" | "L" | "P"`
@@ -54,6 +63,7 @@ impl PathType {
 
 /// The specific type of a [`PathType`].
 #[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SplineType {
     #[default]
     Catmull,
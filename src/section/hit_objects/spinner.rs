@@ -4,6 +4,7 @@ use crate::util::Pos;
 ///
 /// [`HitObject`]: crate::section::hit_objects::HitObject
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HitObjectSpinner {
     pub pos: Pos,
     pub duration: f64,
@@ -0,0 +1,196 @@
+use crate::util::Pos;
+
+use super::{CurveBuffers, HitObject, HitObjectKind};
+
+const STACK_DISTANCE: f32 = 3.0;
+
+/// The information of a [`HitObject`] that is relevant for stacking.
+struct StackObject {
+    start_time: f64,
+    end_time: f64,
+    pos: Pos,
+    /// Position at the end of the last span.
+    end_pos: Pos,
+    /// Position at the end of the first span.
+    tail_pos: Pos,
+    kind: StackObjectKind,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum StackObjectKind {
+    Circle,
+    Slider,
+    /// Objects that are never stacked i.e. spinners and hold notes.
+    Ignored,
+}
+
+impl StackObject {
+    fn new(h: &mut HitObject, bufs: &mut CurveBuffers) -> Self {
+        let end_time = h.end_time_with_bufs(bufs);
+
+        let (pos, end_pos, tail_pos, kind) = match h.kind {
+            HitObjectKind::Circle(ref circle) => {
+                (circle.pos, circle.pos, circle.pos, StackObjectKind::Circle)
+            }
+            HitObjectKind::Slider(ref mut slider) => {
+                let end_pos = slider.end_position(bufs);
+                let tail_pos = slider.pos + slider.path.curve_with_bufs(bufs).position_at(1.0);
+
+                (slider.pos, end_pos, tail_pos, StackObjectKind::Slider)
+            }
+            HitObjectKind::Spinner(ref spinner) => (
+                spinner.pos,
+                spinner.pos,
+                spinner.pos,
+                StackObjectKind::Ignored,
+            ),
+            HitObjectKind::Hold(_) => (
+                Pos::default(),
+                Pos::default(),
+                Pos::default(),
+                StackObjectKind::Ignored,
+            ),
+        };
+
+        Self {
+            start_time: h.start_time,
+            end_time,
+            pos,
+            end_pos,
+            tail_pos,
+            kind,
+        }
+    }
+
+    fn is_ignored(&self) -> bool {
+        self.kind == StackObjectKind::Ignored
+    }
+}
+
+/// Calculates the stack height of each [`HitObject`].
+///
+/// `stack_threshold` is the object's preempt duration multiplied by the
+/// stack leniency.
+pub(crate) fn stack_heights(
+    hit_objects: &mut [HitObject],
+    format_version: i32,
+    stack_threshold: f64,
+) -> Vec<i32> {
+    let mut bufs = CurveBuffers::default();
+
+    let objects: Vec<_> = hit_objects
+        .iter_mut()
+        .map(|h| StackObject::new(h, &mut bufs))
+        .collect();
+
+    let mut heights = vec![0; objects.len()];
+
+    if format_version >= 6 {
+        apply_stacking(&objects, &mut heights, stack_threshold);
+    } else {
+        apply_stacking_old(&objects, &mut heights, stack_threshold);
+    }
+
+    heights
+}
+
+fn apply_stacking(objects: &[StackObject], heights: &mut [i32], stack_threshold: f64) {
+    for i in (1..objects.len()).rev() {
+        let mut object_i = i;
+
+        if heights[object_i] != 0 || objects[object_i].is_ignored() {
+            continue;
+        }
+
+        match objects[object_i].kind {
+            StackObjectKind::Circle => {
+                for n in (0..i).rev() {
+                    let object_n = &objects[n];
+
+                    if object_n.is_ignored() {
+                        continue;
+                    }
+
+                    let curr = &objects[object_i];
+
+                    if curr.start_time - object_n.end_time > stack_threshold {
+                        break;
+                    }
+
+                    // Sliders ending where the circle stacks shift the stack
+                    // away from their end instead
+                    if object_n.kind == StackObjectKind::Slider
+                        && object_n.end_pos.distance(curr.pos) < STACK_DISTANCE
+                    {
+                        let offset = heights[object_i] - heights[n] + 1;
+
+                        for j in n + 1..=i {
+                            if object_n.end_pos.distance(objects[j].pos) < STACK_DISTANCE {
+                                heights[j] -= offset;
+                            }
+                        }
+
+                        break;
+                    }
+
+                    if object_n.pos.distance(curr.pos) < STACK_DISTANCE {
+                        heights[n] = heights[object_i] + 1;
+                        object_i = n;
+                    }
+                }
+            }
+            StackObjectKind::Slider => {
+                for n in (0..i).rev() {
+                    let object_n = &objects[n];
+
+                    if object_n.is_ignored() {
+                        continue;
+                    }
+
+                    let curr = &objects[object_i];
+
+                    if curr.start_time - object_n.start_time > stack_threshold {
+                        break;
+                    }
+
+                    if object_n.end_pos.distance(curr.pos) < STACK_DISTANCE {
+                        heights[n] = heights[object_i] + 1;
+                        object_i = n;
+                    }
+                }
+            }
+            StackObjectKind::Ignored => {}
+        }
+    }
+}
+
+/// Stacking for beatmaps with a format version below 6.
+fn apply_stacking_old(objects: &[StackObject], heights: &mut [i32], stack_threshold: f64) {
+    for i in 0..objects.len() {
+        let curr = &objects[i];
+
+        if heights[i] != 0 && curr.kind != StackObjectKind::Slider {
+            continue;
+        }
+
+        let mut start_time = curr.end_time;
+        let mut slider_stack = 0;
+
+        for j in i + 1..objects.len() {
+            let object_j = &objects[j];
+
+            if object_j.start_time - stack_threshold > start_time {
+                break;
+            }
+
+            if object_j.pos.distance(curr.pos) < STACK_DISTANCE {
+                heights[i] += 1;
+                start_time = object_j.end_time;
+            } else if object_j.pos.distance(curr.tail_pos) < STACK_DISTANCE {
+                slider_stack += 1;
+                heights[j] -= slider_stack;
+                start_time = object_j.end_time;
+            }
+        }
+    }
+}
@@ -6,6 +6,7 @@ use crate::{
 
 /// Struct containing all data from a `.osu` file's `[Metadata]` section.
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Metadata {
     pub title: String,
     pub title_unicode: String,
@@ -37,6 +38,46 @@ impl Default for Metadata {
     }
 }
 
+impl Metadata {
+    /// The title to display, i.e. [`Metadata::title_unicode`] if it is
+    /// non-empty, otherwise [`Metadata::title`].
+    pub fn display_title(&self) -> &str {
+        unicode_or_ascii(&self.title_unicode, &self.title)
+    }
+
+    /// The artist to display, i.e. [`Metadata::artist_unicode`] if it is
+    /// non-empty, otherwise [`Metadata::artist`].
+    pub fn display_artist(&self) -> &str {
+        unicode_or_ascii(&self.artist_unicode, &self.artist)
+    }
+}
+
+/// osu! falls back to the ASCII value if the unicode value is empty.
+pub(crate) const fn unicode_or_ascii<'a>(unicode: &'a str, ascii: &'a str) -> &'a str {
+    if unicode.is_empty() {
+        ascii
+    } else {
+        unicode
+    }
+}
+
+/// Borrowed view on the `[Metadata]` values of a [`Beatmap`].
+///
+/// Created through [`Beatmap::metadata_view`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct MetadataRef<'a> {
+    pub title: &'a str,
+    pub title_unicode: &'a str,
+    pub artist: &'a str,
+    pub artist_unicode: &'a str,
+    pub creator: &'a str,
+    pub version: &'a str,
+    pub source: &'a str,
+    pub tags: &'a str,
+    pub beatmap_id: &'a i32,
+    pub beatmap_set_id: &'a i32,
+}
+
 impl From<Metadata> for Beatmap {
     fn from(metadata: Metadata) -> Self {
         Self {
@@ -22,6 +22,9 @@ pub mod colors;
 /// Types for the `[HitObjects]` section.
 pub mod hit_objects;
 
+/// Types for a summary of the `[General]` and `[Metadata]` sections.
+pub mod summary;
+
 /// All sections in a `.osu` file.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Section {
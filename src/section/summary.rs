@@ -0,0 +1,151 @@
+use crate::{
+    decode::{DecodeBeatmap, DecodeState},
+    section::{
+        general::{GameMode, GeneralKey, ParseGameModeError},
+        metadata::MetadataKey,
+    },
+    util::{KeyValue, ParseNumberError, StrExt},
+    Beatmap,
+};
+
+/// Struct containing the most commonly displayed values of a `.osu` file's
+/// `[General]` and `[Metadata]` sections, e.g. for a song select list.
+///
+/// All other sections are skipped while decoding.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BeatmapSummary {
+    pub audio_file: String,
+    pub mode: GameMode,
+    pub title: String,
+    pub artist: String,
+    pub creator: String,
+    pub version: String,
+    pub beatmap_id: i32,
+    pub beatmap_set_id: i32,
+}
+
+impl Default for BeatmapSummary {
+    #[allow(clippy::default_trait_access)]
+    fn default() -> Self {
+        Self {
+            audio_file: Default::default(),
+            mode: Default::default(),
+            title: Default::default(),
+            artist: Default::default(),
+            creator: Default::default(),
+            version: Default::default(),
+            beatmap_id: -1,
+            beatmap_set_id: Default::default(),
+        }
+    }
+}
+
+impl From<BeatmapSummary> for Beatmap {
+    fn from(summary: BeatmapSummary) -> Self {
+        Self {
+            audio_file: summary.audio_file,
+            mode: summary.mode,
+            title: summary.title,
+            artist: summary.artist,
+            creator: summary.creator,
+            version: summary.version,
+            beatmap_id: summary.beatmap_id,
+            beatmap_set_id: summary.beatmap_set_id,
+            ..Self::default()
+        }
+    }
+}
+
+thiserror! {
+    /// All the ways that parsing a `.osu` file into [`BeatmapSummary`] can fail.
+    #[derive(Debug)]
+    pub enum ParseBeatmapSummaryError {
+        #[error("failed to parse mode")]
+        Mode(#[from] ParseGameModeError),
+        #[error("failed to parse number")]
+        Number(#[from] ParseNumberError),
+    }
+}
+
+/// The parsing state for [`BeatmapSummary`] in [`DecodeBeatmap`].
+pub type BeatmapSummaryState = BeatmapSummary;
+
+impl DecodeState for BeatmapSummaryState {
+    fn create(_: i32) -> Self {
+        Self::default()
+    }
+}
+
+impl DecodeBeatmap for BeatmapSummary {
+    type Error = ParseBeatmapSummaryError;
+    type State = BeatmapSummaryState;
+
+    fn parse_general(state: &mut Self::State, line: &str) -> Result<(), Self::Error> {
+        let Ok(KeyValue { key, value }) = KeyValue::parse(line.trim_comment()) else {
+            return Ok(());
+        };
+
+        match key {
+            GeneralKey::AudioFilename => state.audio_file = value.to_standardized_path(),
+            GeneralKey::Mode => state.mode = value.parse()?,
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn parse_editor(_: &mut Self::State, _: &str) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn parse_metadata(state: &mut Self::State, line: &str) -> Result<(), Self::Error> {
+        let Ok(KeyValue { key, value }) = KeyValue::parse(line) else {
+            return Ok(());
+        };
+
+        match key {
+            MetadataKey::Title => value.clone_into(&mut state.title),
+            MetadataKey::Artist => value.clone_into(&mut state.artist),
+            MetadataKey::Creator => value.clone_into(&mut state.creator),
+            MetadataKey::Version => value.clone_into(&mut state.version),
+            MetadataKey::BeatmapID => state.beatmap_id = value.parse_num()?,
+            MetadataKey::BeatmapSetID => state.beatmap_set_id = value.parse_num()?,
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn parse_difficulty(_: &mut Self::State, _: &str) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn parse_events(_: &mut Self::State, _: &str) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn parse_timing_points(_: &mut Self::State, _: &str) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn parse_colors(_: &mut Self::State, _: &str) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn parse_hit_objects(_: &mut Self::State, _: &str) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn parse_variables(_: &mut Self::State, _: &str) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn parse_catch_the_beat(_: &mut Self::State, _: &str) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn parse_mania(_: &mut Self::State, _: &str) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
@@ -2,6 +2,7 @@ use std::cmp::Ordering;
 
 /// Difficulty-related info about this control point.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DifficultyPoint {
     pub time: f64,
     pub slider_velocity: f64,
@@ -12,14 +13,70 @@ impl DifficultyPoint {
     pub const DEFAULT_SLIDER_VELOCITY: f64 = 1.0;
     pub const DEFAULT_GENERATE_TICKS: bool = true;
 
-    pub fn new(time: f64, beat_len: f64, speed_multiplier: f64) -> Self {
+    /// The lowest slider velocity osu! allows.
+    pub const MIN_SLIDER_VELOCITY: f64 = 0.1;
+    /// The highest slider velocity osu! allows.
+    pub const MAX_SLIDER_VELOCITY: f64 = 10.0;
+
+    pub const fn new(time: f64, beat_len: f64, speed_multiplier: f64) -> Self {
         Self {
             time,
-            slider_velocity: speed_multiplier.clamp(0.1, 10.0),
+            slider_velocity: speed_multiplier
+                .clamp(Self::MIN_SLIDER_VELOCITY, Self::MAX_SLIDER_VELOCITY),
             generate_ticks: !beat_len.is_nan(),
         }
     }
 
+    /// Clamp the slider velocity between [`MIN_SLIDER_VELOCITY`] and
+    /// [`MAX_SLIDER_VELOCITY`].
+    ///
+    /// Points created through [`DifficultyPoint::new`], and thus all decoded
+    /// points, are already clamped. This is only useful after modifying
+    /// `slider_velocity` manually.
+    ///
+    /// [`MIN_SLIDER_VELOCITY`]: Self::MIN_SLIDER_VELOCITY
+    /// [`MAX_SLIDER_VELOCITY`]: Self::MAX_SLIDER_VELOCITY
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rosu_map::section::timing_points::DifficultyPoint;
+    ///
+    /// let mut point = DifficultyPoint::new(1000.0, -100.0, 1.0);
+    ///
+    /// point.slider_velocity = 25.0;
+    /// point.clamp_slider_velocity();
+    /// assert_eq!(point.slider_velocity, DifficultyPoint::MAX_SLIDER_VELOCITY);
+    ///
+    /// point.slider_velocity = 0.0;
+    /// point.clamp_slider_velocity();
+    /// assert_eq!(point.slider_velocity, DifficultyPoint::MIN_SLIDER_VELOCITY);
+    /// ```
+    pub const fn clamp_slider_velocity(&mut self) {
+        self.slider_velocity = self
+            .slider_velocity
+            .clamp(Self::MIN_SLIDER_VELOCITY, Self::MAX_SLIDER_VELOCITY);
+    }
+
+    /// Whether `self` would not change anything if it was added after
+    /// `existing`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rosu_map::section::timing_points::{ControlPoints, DifficultyPoint};
+    ///
+    /// let mut control_points = ControlPoints::default();
+    /// control_points.add(DifficultyPoint::new(1000.0, -50.0, 2.0));
+    ///
+    /// let new_point = DifficultyPoint::new(2000.0, -50.0, 2.0);
+    /// let active = control_points.difficulty_point_at(new_point.time).unwrap();
+    /// assert!(new_point.is_redundant(active));
+    ///
+    /// let new_point = DifficultyPoint::new(2000.0, -100.0, 1.0);
+    /// let active = control_points.difficulty_point_at(new_point.time).unwrap();
+    /// assert!(!new_point.is_redundant(active));
+    /// ```
     pub fn is_redundant(&self, existing: &Self) -> bool {
         self.generate_ticks == existing.generate_ticks
             && (self.slider_velocity - existing.slider_velocity).abs() < f64::EPSILON
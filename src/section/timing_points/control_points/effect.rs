@@ -1,6 +1,7 @@
 use std::cmp::Ordering;
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// Effect-related info about this control point.
 pub struct EffectPoint {
     pub time: f64,
@@ -20,6 +21,8 @@ impl EffectPoint {
         }
     }
 
+    /// Whether `self` would not change anything if it was added after
+    /// `existing`.
     pub fn is_redundant(&self, existing: &Self) -> bool {
         self.kiai == existing.kiai
             && (self.scroll_speed - existing.scroll_speed).abs() < f64::EPSILON
@@ -3,6 +3,10 @@ use std::{cmp::Ordering, num::NonZeroU32};
 use crate::section::hit_objects::hit_samples::{HitSampleInfo, HitSampleInfoName, SampleBank};
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+// `unsafe` is only used to create `NonZeroU32` values whose invariant is
+// checked on deserialization as well
+#[cfg_attr(feature = "serde", allow(clippy::unsafe_derive_deserialize))]
 /// Audio-related info about this control point.
 pub struct SamplePoint {
     pub time: f64,
@@ -30,6 +34,8 @@ impl SamplePoint {
         }
     }
 
+    /// Whether `self` would not change anything if it was added after
+    /// `existing`.
     pub fn is_redundant(&self, existing: &Self) -> bool {
         self.sample_bank == existing.sample_bank
             && self.sample_volume == existing.sample_volume
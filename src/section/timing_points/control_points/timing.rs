@@ -1,6 +1,7 @@
 use std::{cmp::Ordering, num::NonZeroU32};
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// The time signature at this control point.
 pub struct TimingPoint {
     pub time: f64,
@@ -14,7 +15,7 @@ impl TimingPoint {
     pub const DEFAULT_OMIT_FIRST_BAR_LINE: bool = false;
     pub const DEFAULT_TIME_SIGNATURE: TimeSignature = TimeSignature::new_simple_quadruple();
 
-    pub fn new(
+    pub const fn new(
         time: f64,
         beat_len: f64,
         omit_first_bar_line: bool,
@@ -27,6 +28,22 @@ impl TimingPoint {
             time_signature,
         }
     }
+
+    /// Create a new [`TimingPoint`] by specifying its beats per minute
+    /// instead of its beat length.
+    pub const fn from_bpm(
+        time: f64,
+        bpm: f64,
+        omit_first_bar_line: bool,
+        time_signature: TimeSignature,
+    ) -> Self {
+        Self::new(time, 60_000.0 / bpm, omit_first_bar_line, time_signature)
+    }
+
+    /// The beats per minute of the [`TimingPoint`].
+    pub const fn bpm(&self) -> f64 {
+        60_000.0 / self.beat_len
+    }
 }
 
 impl Default for TimingPoint {
@@ -47,6 +64,10 @@ impl PartialOrd for TimingPoint {
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+// `unsafe` is only used to create `NonZeroU32` values whose invariant is
+// checked on deserialization as well
+#[cfg_attr(feature = "serde", allow(clippy::unsafe_derive_deserialize))]
 /// The time signature of a track.
 pub struct TimeSignature {
     pub numerator: NonZeroU32,
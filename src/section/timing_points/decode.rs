@@ -1,3 +1,5 @@
+use std::{convert::identity, fmt::Write, mem};
+
 use crate::{
     decode::{DecodeBeatmap, DecodeState},
     section::{
@@ -16,6 +18,7 @@ use super::{
 /// Struct containing all data from a `.osu` file's `[TimingPoints]` and
 /// `[General]` section.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TimingPoints {
     // General
     pub audio_file: String,
@@ -86,6 +89,7 @@ impl From<TimingPoints> for Beatmap {
 
 /// All control points of a [`Beatmap`].
 #[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ControlPoints {
     pub timing_points: Vec<TimingPoint>,
     pub difficulty_points: Vec<DifficultyPoint>,
@@ -93,7 +97,109 @@ pub struct ControlPoints {
     pub sample_points: Vec<SamplePoint>,
 }
 
+/// A reference to any kind of control point.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ControlPointRef<'a> {
+    Timing(&'a TimingPoint),
+    Difficulty(&'a DifficultyPoint),
+    Effect(&'a EffectPoint),
+    Sample(&'a SamplePoint),
+}
+
+impl ControlPointRef<'_> {
+    /// The time of the control point.
+    pub const fn time(&self) -> f64 {
+        match self {
+            Self::Timing(point) => point.time,
+            Self::Difficulty(point) => point.time,
+            Self::Effect(point) => point.time,
+            Self::Sample(point) => point.time,
+        }
+    }
+}
+
 impl ControlPoints {
+    /// Lists all control points ordered by their time with one line per
+    /// control point.
+    ///
+    /// This is meant for debugging and not the `.osu` file format.
+    pub fn debug_timeline(&self) -> String {
+        let mut timeline = String::new();
+
+        for point in self.iter_all() {
+            let _ = match point {
+                ControlPointRef::Timing(point) => writeln!(
+                    timeline,
+                    "{}ms timing: {:.2} BPM, {}/4",
+                    point.time,
+                    point.bpm(),
+                    point.time_signature.numerator,
+                ),
+                ControlPointRef::Difficulty(point) => writeln!(
+                    timeline,
+                    "{}ms difficulty: {:.2}x SV{}",
+                    point.time,
+                    point.slider_velocity,
+                    if point.generate_ticks {
+                        ""
+                    } else {
+                        ", no ticks"
+                    },
+                ),
+                ControlPointRef::Effect(point) => writeln!(
+                    timeline,
+                    "{}ms effect: kiai {}, {:.2}x scroll speed",
+                    point.time,
+                    if point.kiai { "on" } else { "off" },
+                    point.scroll_speed,
+                ),
+                ControlPointRef::Sample(point) => writeln!(
+                    timeline,
+                    "{}ms sample: {} bank, {}% volume, custom bank {}",
+                    point.time, point.sample_bank, point.sample_volume, point.custom_sample_bank,
+                ),
+            };
+        }
+
+        timeline
+    }
+
+    /// Iterates over all control points ordered by their time.
+    ///
+    /// Control points at the same time are yielded in the order timing,
+    /// difficulty, sample, and effect point, matching how they are grouped
+    /// when encoding.
+    pub fn iter_all(&self) -> impl Iterator<Item = ControlPointRef<'_>> {
+        let mut timing = self.timing_points.iter().peekable();
+        let mut difficulty = self.difficulty_points.iter().peekable();
+        let mut sample = self.sample_points.iter().peekable();
+        let mut effect = self.effect_points.iter().peekable();
+
+        std::iter::from_fn(move || {
+            let heads = [
+                timing.peek().map(|point| point.time),
+                difficulty.peek().map(|point| point.time),
+                sample.peek().map(|point| point.time),
+                effect.peek().map(|point| point.time),
+            ];
+
+            // Only a strictly smaller time replaces the current minimum so
+            // that ties keep the order of `heads`
+            let (idx, _) = heads
+                .into_iter()
+                .enumerate()
+                .filter_map(|(i, time)| Some((i, time?)))
+                .reduce(|min, curr| if curr.1 < min.1 { curr } else { min })?;
+
+            match idx {
+                0 => timing.next().map(ControlPointRef::Timing),
+                1 => difficulty.next().map(ControlPointRef::Difficulty),
+                2 => sample.next().map(ControlPointRef::Sample),
+                _ => effect.next().map(ControlPointRef::Effect),
+            }
+        })
+    }
+
     /// Finds the [`DifficultyPoint`] that is active at the given time.
     pub fn difficulty_point_at(&self, time: f64) -> Option<&DifficultyPoint> {
         self.difficulty_points
@@ -130,12 +236,216 @@ impl ControlPoints {
         self.timing_points.get(i)
     }
 
+    /// The `(time, bpm)` of each [`TimingPoint`] that changes the BPM.
+    ///
+    /// Timing points whose beat length matches that of the previous timing
+    /// point are skipped.
+    pub fn bpm_changes(&self) -> Vec<(f64, f64)> {
+        let mut changes = Vec::new();
+        let mut prev_beat_len = None;
+
+        for point in &self.timing_points {
+            if prev_beat_len
+                .is_some_and(|beat_len: f64| (point.beat_len - beat_len).abs() < f64::EPSILON)
+            {
+                continue;
+            }
+
+            prev_beat_len = Some(point.beat_len);
+            changes.push((point.time, 60_000.0 / point.beat_len));
+        }
+
+        changes
+    }
+
+    /// Whether there are [`DifficultyPoint`]s, i.e. inherited timing points,
+    /// before the first [`TimingPoint`].
+    ///
+    /// Such difficulty points are usually a mapping mistake because their
+    /// slider velocity relates to a beat length that has not been defined yet.
+    /// They are still decoded and applied like any other difficulty point but
+    /// osu!stable does not consider them consistently so they may lead to
+    /// differing slider velocities.
+    pub fn has_orphan_difficulty_points(&self) -> bool {
+        let Some(first_difficulty) = self.difficulty_points.first() else {
+            return false;
+        };
+
+        self.timing_points
+            .first()
+            .is_none_or(|first_timing| first_difficulty.time < first_timing.time)
+    }
+
+    /// Whether the [`TimingPoint`] active at the given time omits the first
+    /// bar line.
+    pub fn omits_first_bar_line_at(&self, time: f64) -> bool {
+        self.timing_point_at(time)
+            .map_or(TimingPoint::DEFAULT_OMIT_FIRST_BAR_LINE, |point| {
+                point.omit_first_bar_line
+            })
+    }
+
+    /// Round `time` to the nearest beat snap of the given divisor.
+    ///
+    /// The grid of beat snaps starts at the [`TimingPoint`] active at `time`
+    /// and each snap is a `1/divisor` of its beat length. If there are no
+    /// timing points, the grid starts at `0.0` with the default beat length.
+    /// If `divisor` is not positive, `time` is returned as is.
+    pub fn snap_time(&self, time: f64, divisor: i32) -> f64 {
+        if divisor <= 0 {
+            return time;
+        }
+
+        let (start_time, beat_len) = self
+            .timing_point_at(time)
+            .map_or((0.0, TimingPoint::DEFAULT_BEAT_LEN), |point| {
+                (point.time, point.beat_len)
+            });
+
+        let snap_len = beat_len / f64::from(divisor);
+        let snaps = ((time - start_time) / snap_len).round();
+
+        start_time + snaps * snap_len
+    }
+
+    /// Remove all control points whose time is after the given time.
+    ///
+    /// Control points at or before `time` are kept so the points that are
+    /// active at `time` remain the same.
+    pub fn truncate_after(&mut self, time: f64) {
+        self.timing_points.retain(|point| point.time <= time);
+        self.difficulty_points.retain(|point| point.time <= time);
+        self.effect_points.retain(|point| point.time <= time);
+        self.sample_points.retain(|point| point.time <= time);
+    }
+
+    /// Remove control points that share their time with a subsequent point of
+    /// the same kind so that only the last point at each time remains.
+    ///
+    /// The lists are expected to be sorted by time.
+    pub fn dedup_by_time(&mut self) {
+        dedup_keep_last(&mut self.timing_points, |point| point.time);
+        dedup_keep_last(&mut self.difficulty_points, |point| point.time);
+        dedup_keep_last(&mut self.effect_points, |point| point.time);
+        dedup_keep_last(&mut self.sample_points, |point| point.time);
+    }
+
     /// Add a [`ControlPoint`] into its corresponding list.
     pub fn add<P: ControlPoint<ControlPoints>>(&mut self, point: P) {
         if !point.check_already_existing(self) {
             point.add(self);
         }
     }
+
+    /// Add a [`TimingPoint`] while keeping the list sorted.
+    ///
+    /// Redundant points are discarded, just like when decoding.
+    /// See [`ControlPoints::add`].
+    pub fn add_timing_point(&mut self, point: TimingPoint) {
+        self.add(point);
+    }
+
+    /// Add a [`DifficultyPoint`] while keeping the list sorted.
+    ///
+    /// Redundant points are discarded, just like when decoding.
+    /// See [`ControlPoints::add`].
+    pub fn add_difficulty_point(&mut self, point: DifficultyPoint) {
+        self.add(point);
+    }
+
+    /// Add a [`EffectPoint`] while keeping the list sorted.
+    ///
+    /// Redundant points are discarded, just like when decoding.
+    /// See [`ControlPoints::add`].
+    pub fn add_effect_point(&mut self, point: EffectPoint) {
+        self.add(point);
+    }
+
+    /// Add a [`SamplePoint`] while keeping the list sorted.
+    ///
+    /// Redundant points are discarded, just like when decoding.
+    /// See [`ControlPoints::add`].
+    pub fn add_sample_point(&mut self, point: SamplePoint) {
+        self.add(point);
+    }
+
+    /// The index at which a [`TimingPoint`] at the given time would be inserted
+    /// into [`ControlPoints::timing_points`].
+    ///
+    /// If a point already exists at exactly that time, its index is returned.
+    pub fn timing_insert_index(&self, time: f64) -> usize {
+        insert_index(&self.timing_points, time, |point| point.time)
+    }
+
+    /// The index at which a [`DifficultyPoint`] at the given time would be inserted
+    /// into [`ControlPoints::difficulty_points`].
+    ///
+    /// If a point already exists at exactly that time, its index is returned.
+    pub fn difficulty_insert_index(&self, time: f64) -> usize {
+        insert_index(&self.difficulty_points, time, |point| point.time)
+    }
+
+    /// The index at which a [`EffectPoint`] at the given time would be inserted
+    /// into [`ControlPoints::effect_points`].
+    ///
+    /// If a point already exists at exactly that time, its index is returned.
+    pub fn effect_insert_index(&self, time: f64) -> usize {
+        insert_index(&self.effect_points, time, |point| point.time)
+    }
+
+    /// The index at which a [`SamplePoint`] at the given time would be inserted
+    /// into [`ControlPoints::sample_points`].
+    ///
+    /// If a point already exists at exactly that time, its index is returned.
+    pub fn sample_insert_index(&self, time: f64) -> usize {
+        insert_index(&self.sample_points, time, |point| point.time)
+    }
+
+    /// Remove the [`TimingPoint`] at exactly the given time.
+    pub fn remove_timing_point_at(&mut self, time: f64) -> Option<TimingPoint> {
+        remove_at(&mut self.timing_points, time, |point| point.time)
+    }
+
+    /// Remove the [`DifficultyPoint`] at exactly the given time.
+    pub fn remove_difficulty_point_at(&mut self, time: f64) -> Option<DifficultyPoint> {
+        remove_at(&mut self.difficulty_points, time, |point| point.time)
+    }
+
+    /// Remove the [`EffectPoint`] at exactly the given time.
+    pub fn remove_effect_point_at(&mut self, time: f64) -> Option<EffectPoint> {
+        remove_at(&mut self.effect_points, time, |point| point.time)
+    }
+
+    /// Remove the [`SamplePoint`] at exactly the given time.
+    pub fn remove_sample_point_at(&mut self, time: f64) -> Option<SamplePoint> {
+        remove_at(&mut self.sample_points, time, |point| point.time)
+    }
+}
+
+fn insert_index<P>(points: &[P], time: f64, get_time: impl Fn(&P) -> f64) -> usize {
+    points
+        .binary_search_by(|probe| get_time(probe).total_cmp(&time))
+        .unwrap_or_else(identity)
+}
+
+fn remove_at<P>(points: &mut Vec<P>, time: f64, get_time: impl Fn(&P) -> f64) -> Option<P> {
+    points
+        .binary_search_by(|probe| get_time(probe).total_cmp(&time))
+        .ok()
+        .map(|i| points.remove(i))
+}
+
+fn dedup_keep_last<P>(points: &mut Vec<P>, get_time: impl Fn(&P) -> f64) {
+    points.dedup_by(|next, prev| {
+        let duplicate = get_time(next).total_cmp(&get_time(prev)).is_eq();
+
+        if duplicate {
+            // `next` is the one getting removed so keep its value
+            mem::swap(next, prev);
+        }
+
+        duplicate
+    });
 }
 
 /// A control point to be added into a collection of type `C`.
@@ -209,9 +519,7 @@ impl ControlPoint<ControlPoints> for SamplePoint {
             .sample_points
             .binary_search_by(|probe| probe.time.total_cmp(&self.time))
             .map_or_else(|i| i.checked_sub(1), Some)
-            .map_or(false, |i| {
-                self.is_redundant(&control_points.sample_points[i])
-            })
+            .is_some_and(|i| self.is_redundant(&control_points.sample_points[i]))
     }
 
     fn add(self, control_points: &mut ControlPoints) {
@@ -416,9 +724,9 @@ impl DecodeBeatmap for TimingPoints {
             .map_err(ParseNumberError::InvalidFloat)?;
 
         if beat_len < f64::from(-MAX_PARSE_VALUE) {
-            return Err(ParseNumberError::NumberUnderflow.into());
+            return Err(ParseNumberError::underflow(beat_len, f64::from(MAX_PARSE_VALUE)).into());
         } else if beat_len > f64::from(MAX_PARSE_VALUE) {
-            return Err(ParseNumberError::NumberOverflow.into());
+            return Err(ParseNumberError::overflow(beat_len, f64::from(MAX_PARSE_VALUE)).into());
         }
 
         let speed_multiplier = if beat_len < 0.0 {
@@ -427,38 +735,38 @@ impl DecodeBeatmap for TimingPoints {
             1.0
         };
 
+        // Empty values, e.g. due to a trailing comma, are treated as missing
+        let mut next_value = || split.next().filter(|value| !value.trim().is_empty());
+
         let mut time_signature = TimeSignature::new_simple_quadruple();
 
-        if let Some(next) = split.next() {
+        if let Some(next) = next_value() {
             if !matches!(next.chars().next(), Some('0')) {
                 time_signature = TimeSignature::new(next.parse_num()?)?;
             }
         }
 
-        let mut sample_set = split
-            .next()
+        let mut sample_set = next_value()
             .map(i32::parse)
             .transpose()?
             .map(SampleBank::try_from)
             .and_then(Result::ok)
             .unwrap_or(state.general.default_sample_bank);
 
-        let custom_sample_bank = split.next().map(i32::parse).transpose()?.unwrap_or(0);
+        let custom_sample_bank = next_value().map(i32::parse).transpose()?.unwrap_or(0);
 
-        let sample_volume = split
-            .next()
+        let sample_volume = next_value()
             .map(i32::parse)
             .transpose()?
             .unwrap_or(state.general.default_sample_volume);
 
-        let timing_change = split
-            .next()
-            .map_or(true, |next| matches!(next.chars().next(), Some('1')));
+        let timing_change =
+            next_value().is_none_or(|next| matches!(next.chars().next(), Some('1')));
 
         let mut kiai_mode = false;
         let mut omit_first_bar_signature = false;
 
-        if let Some(next) = split.next() {
+        if let Some(next) = next_value() {
             let effect_flags: EffectFlags = next.parse()?;
             kiai_mode = effect_flags.has_flag(EffectFlags::KIAI);
             omit_first_bar_signature = effect_flags.has_flag(EffectFlags::OMIT_FIRST_BAR_LINE);
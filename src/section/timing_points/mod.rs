@@ -6,7 +6,8 @@ pub use self::{
         timing::{TimeSignature, TimeSignatureError, TimingPoint},
     },
     decode::{
-        ControlPoint, ControlPoints, ParseTimingPointsError, TimingPoints, TimingPointsState,
+        ControlPoint, ControlPointRef, ControlPoints, ParseTimingPointsError, TimingPoints,
+        TimingPointsState,
     },
     effect_flags::{EffectFlags, ParseEffectFlagsError},
 };
@@ -0,0 +1,194 @@
+use crate::section::{
+    colors::Color,
+    events::BreakPeriod,
+    hit_objects::{
+        HitObject, HitObjectCircle, HitObjectHold, HitObjectKind, HitObjectSlider,
+        HitObjectSpinner, PathControlPoint, SliderPath,
+    },
+    timing_points::{ControlPoints, DifficultyPoint, EffectPoint, SamplePoint, TimingPoint},
+};
+
+use super::Pos;
+
+/// Equality comparison that allows floating point values to differ by a
+/// given epsilon.
+pub(crate) trait ApproxEq {
+    fn approx_eq(&self, other: &Self, epsilon: f64) -> bool;
+}
+
+impl ApproxEq for f64 {
+    fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        (self.is_nan() && other.is_nan()) || (self - other).abs() <= epsilon
+    }
+}
+
+impl ApproxEq for f32 {
+    fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        f64::from(*self).approx_eq(&f64::from(*other), epsilon)
+    }
+}
+
+impl<T: ApproxEq> ApproxEq for Option<T> {
+    fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        match (self, other) {
+            (Some(a), Some(b)) => a.approx_eq(b, epsilon),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+impl<T: ApproxEq> ApproxEq for [T] {
+    fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        self.len() == other.len() && self.iter().zip(other).all(|(a, b)| a.approx_eq(b, epsilon))
+    }
+}
+
+impl<T: ApproxEq> ApproxEq for Vec<T> {
+    fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        self.as_slice().approx_eq(other.as_slice(), epsilon)
+    }
+}
+
+impl<A: ApproxEq, B: ApproxEq> ApproxEq for (A, B) {
+    fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        self.0.approx_eq(&other.0, epsilon) && self.1.approx_eq(&other.1, epsilon)
+    }
+}
+
+impl ApproxEq for Color {
+    fn approx_eq(&self, other: &Self, _: f64) -> bool {
+        self == other
+    }
+}
+
+impl ApproxEq for Pos {
+    fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        self.x.approx_eq(&other.x, epsilon) && self.y.approx_eq(&other.y, epsilon)
+    }
+}
+
+impl ApproxEq for BreakPeriod {
+    fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        self.start_time.approx_eq(&other.start_time, epsilon)
+            && self.end_time.approx_eq(&other.end_time, epsilon)
+    }
+}
+
+impl ApproxEq for ControlPoints {
+    fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        self.timing_points.approx_eq(&other.timing_points, epsilon)
+            && self
+                .difficulty_points
+                .approx_eq(&other.difficulty_points, epsilon)
+            && self.effect_points.approx_eq(&other.effect_points, epsilon)
+            && self.sample_points.approx_eq(&other.sample_points, epsilon)
+    }
+}
+
+impl ApproxEq for TimingPoint {
+    fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        self.time.approx_eq(&other.time, epsilon)
+            && self.beat_len.approx_eq(&other.beat_len, epsilon)
+            && self.omit_first_bar_line == other.omit_first_bar_line
+            && self.time_signature == other.time_signature
+    }
+}
+
+impl ApproxEq for DifficultyPoint {
+    fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        self.time.approx_eq(&other.time, epsilon)
+            && self
+                .slider_velocity
+                .approx_eq(&other.slider_velocity, epsilon)
+            && self.generate_ticks == other.generate_ticks
+    }
+}
+
+impl ApproxEq for EffectPoint {
+    fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        self.time.approx_eq(&other.time, epsilon)
+            && self.kiai == other.kiai
+            && self.scroll_speed.approx_eq(&other.scroll_speed, epsilon)
+    }
+}
+
+impl ApproxEq for SamplePoint {
+    fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        self.time.approx_eq(&other.time, epsilon)
+            && self.sample_bank == other.sample_bank
+            && self.sample_volume == other.sample_volume
+            && self.custom_sample_bank == other.custom_sample_bank
+    }
+}
+
+impl ApproxEq for HitObject {
+    fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        self.start_time.approx_eq(&other.start_time, epsilon)
+            && self.kind.approx_eq(&other.kind, epsilon)
+            && self.samples == other.samples
+    }
+}
+
+impl ApproxEq for HitObjectKind {
+    fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        match (self, other) {
+            (Self::Circle(a), Self::Circle(b)) => a.approx_eq(b, epsilon),
+            (Self::Slider(a), Self::Slider(b)) => a.approx_eq(b, epsilon),
+            (Self::Spinner(a), Self::Spinner(b)) => a.approx_eq(b, epsilon),
+            (Self::Hold(a), Self::Hold(b)) => a.approx_eq(b, epsilon),
+            _ => false,
+        }
+    }
+}
+
+impl ApproxEq for HitObjectCircle {
+    fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        self.pos.approx_eq(&other.pos, epsilon)
+            && self.new_combo == other.new_combo
+            && self.combo_offset == other.combo_offset
+            && self.stack_height == other.stack_height
+    }
+}
+
+impl ApproxEq for HitObjectSlider {
+    fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        self.pos.approx_eq(&other.pos, epsilon)
+            && self.new_combo == other.new_combo
+            && self.combo_offset == other.combo_offset
+            && self.path.approx_eq(&other.path, epsilon)
+            && self.node_samples == other.node_samples
+            && self.repeat_count == other.repeat_count
+            && self.velocity.approx_eq(&other.velocity, epsilon)
+            && self.stack_height == other.stack_height
+    }
+}
+
+impl ApproxEq for SliderPath {
+    fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        // Same as the `PartialEq` implementation, only control points matter
+        self.control_points()
+            .approx_eq(other.control_points(), epsilon)
+    }
+}
+
+impl ApproxEq for PathControlPoint {
+    fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        self.pos.approx_eq(&other.pos, epsilon) && self.path_type == other.path_type
+    }
+}
+
+impl ApproxEq for HitObjectSpinner {
+    fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        self.pos.approx_eq(&other.pos, epsilon)
+            && self.duration.approx_eq(&other.duration, epsilon)
+            && self.new_combo == other.new_combo
+    }
+}
+
+impl ApproxEq for HitObjectHold {
+    fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        self.pos_x.approx_eq(&other.pos_x, epsilon)
+            && self.duration.approx_eq(&other.duration, epsilon)
+    }
+}
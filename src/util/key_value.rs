@@ -26,11 +26,11 @@ impl<'a, K: FromStr> KeyValue<'a, K> {
     /// assert_eq!(kv.value, "9.3 // Some comment");
     /// ```
     pub fn parse(s: &'a str) -> Result<Self, K::Err> {
-        let mut split = s.split(':').map(str::trim);
+        let (key, value) = s.split_once(':').unwrap_or((s, ""));
 
         Ok(Self {
-            key: split.next().unwrap_or(s.trim()).parse()?,
-            value: split.next().unwrap_or_default(),
+            key: key.trim().parse()?,
+            value: value.trim(),
         })
     }
 }
@@ -64,6 +64,17 @@ mod tests {
         assert_eq!(kv.value, "value");
     }
 
+    #[test]
+    fn value_with_colon() {
+        let kv = KeyValue::<Key>::parse("key: 02:44:471").unwrap();
+        assert_eq!(kv.key, Key);
+        assert_eq!(kv.value, "02:44:471");
+
+        let kv = KeyValue::<Key>::parse("key:a:b:c").unwrap();
+        assert_eq!(kv.key, Key);
+        assert_eq!(kv.value, "a:b:c");
+    }
+
     #[test]
     fn only_key() {
         let kv = KeyValue::<Key>::parse("key:").unwrap();
@@ -84,5 +95,6 @@ mod tests {
     #[test]
     fn no_colon() {
         assert!(KeyValue::<Key>::parse("key value").is_err());
+        assert!(KeyValue::<Key>::parse("NoColon").is_err());
     }
 }
@@ -2,10 +2,15 @@ pub use self::{
     key_value::KeyValue,
     parse_number::{ParseNumber, ParseNumberError, MAX_PARSE_VALUE},
     pos::Pos,
+    sorted_vec::{SortedVec, SortedVecOrd},
     str_ext::StrExt,
 };
 
+pub(crate) use self::approx_eq::ApproxEq;
+
+mod approx_eq;
 mod key_value;
 mod parse_number;
 mod pos;
+mod sorted_vec;
 mod str_ext;
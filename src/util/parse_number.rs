@@ -22,10 +22,20 @@ thiserror! {
         InvalidInteger(#[from] num::ParseIntError),
         #[error("not a number")]
         NaN,
-        #[error("value is too high")]
-        NumberOverflow,
-        #[error("value is too low")]
-        NumberUnderflow,
+        #[error("value {value} is too high, limit is {limit}")]
+        NumberOverflow { value: f64, limit: f64 },
+        #[error("value {value} is too low, limit is -{limit}")]
+        NumberUnderflow { value: f64, limit: f64 },
+    }
+}
+
+impl ParseNumberError {
+    pub(crate) const fn overflow(value: f64, limit: f64) -> Self {
+        Self::NumberOverflow { value, limit }
+    }
+
+    pub(crate) const fn underflow(value: f64, limit: f64) -> Self {
+        Self::NumberUnderflow { value, limit }
     }
 }
 
@@ -38,9 +48,9 @@ impl ParseNumber for i32 {
         let n: Self = s.trim().parse()?;
 
         if n < -limit {
-            Err(ParseNumberError::NumberUnderflow)
+            Err(ParseNumberError::underflow(f64::from(n), f64::from(limit)))
         } else if n > limit {
-            Err(ParseNumberError::NumberOverflow)
+            Err(ParseNumberError::overflow(f64::from(n), f64::from(limit)))
         } else {
             Ok(n)
         }
@@ -56,9 +66,9 @@ impl ParseNumber for f32 {
         let n: Self = s.trim().parse()?;
 
         if n < -limit {
-            Err(ParseNumberError::NumberUnderflow)
+            Err(ParseNumberError::underflow(f64::from(n), f64::from(limit)))
         } else if n > limit {
-            Err(ParseNumberError::NumberOverflow)
+            Err(ParseNumberError::overflow(f64::from(n), f64::from(limit)))
         } else if n.is_nan() {
             Err(ParseNumberError::NaN)
         } else {
@@ -76,9 +86,9 @@ impl ParseNumber for f64 {
         let n: Self = s.trim().parse()?;
 
         if n < -limit {
-            Err(ParseNumberError::NumberUnderflow)
+            Err(ParseNumberError::underflow(n, limit))
         } else if n > limit {
-            Err(ParseNumberError::NumberOverflow)
+            Err(ParseNumberError::overflow(n, limit))
         } else if n.is_nan() {
             Err(ParseNumberError::NaN)
         } else {
@@ -86,3 +96,22 @@ impl ParseNumber for f64 {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overflow_message() {
+        let err = i32::parse("3000000000").unwrap_err();
+        assert!(matches!(err, ParseNumberError::InvalidInteger(_)));
+
+        let err = f64::parse("3000000000").unwrap_err();
+        let msg = err.to_string();
+        assert!(msg.contains("3000000000"), "{msg}");
+        assert!(msg.contains(&MAX_PARSE_VALUE.to_string()), "{msg}");
+
+        let err = i32::parse_with_limits("-20", 10).unwrap_err();
+        assert_eq!(err.to_string(), "value -20 is too low, limit is -10");
+    }
+}
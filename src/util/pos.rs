@@ -5,6 +5,7 @@ use std::{
 
 /// Simple `(x, y)` coordinate / vector
 #[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Pos {
     /// Position on the x-axis.
     pub x: f32,
@@ -47,6 +48,38 @@ impl Pos {
 
         self
     }
+
+    /// Rotate the position around the origin by `radians`.
+    #[must_use]
+    pub fn rotate(self, radians: f32) -> Self {
+        let (sin, cos) = radians.sin_cos();
+
+        Self::new(self.x * cos - self.y * sin, self.x * sin + self.y * cos)
+    }
+
+    /// Rotate the position around `pivot` by `radians`.
+    #[must_use]
+    pub fn rotate_around(self, pivot: Self, radians: f32) -> Self {
+        (self - pivot).rotate(radians) + pivot
+    }
+
+    /// Scale the position's distance to `pivot` by `factor`.
+    #[must_use]
+    pub const fn scale_around(self, pivot: Self, factor: f32) -> Self {
+        Self::new(
+            pivot.x + (self.x - pivot.x) * factor,
+            pivot.y + (self.y - pivot.y) * factor,
+        )
+    }
+
+    /// Return the position halfway between `self` and `other`.
+    #[must_use]
+    pub const fn midpoint(self, other: Self) -> Self {
+        Self::new(
+            f32::midpoint(self.x, other.x),
+            f32::midpoint(self.y, other.y),
+        )
+    }
 }
 
 impl Add for Pos {
@@ -120,3 +153,61 @@ impl Display for Pos {
         write!(f, "({}, {})", self.x, self.y)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use super::*;
+
+    fn assert_close(actual: Pos, expected: Pos) {
+        assert!(
+            actual.distance(expected) < 1e-5,
+            "{actual} is not close to {expected}"
+        );
+    }
+
+    #[test]
+    fn rotate() {
+        let pos = Pos::new(3.0, 4.0);
+
+        assert_eq!(pos.rotate(0.0), pos);
+        assert_close(pos.rotate(FRAC_PI_2), Pos::new(-4.0, 3.0));
+    }
+
+    #[test]
+    fn rotate_around() {
+        let pivot = Pos::new(1.0, 1.0);
+
+        assert_eq!(
+            Pos::new(3.0, 1.0).rotate_around(pivot, 0.0),
+            Pos::new(3.0, 1.0)
+        );
+        assert_close(
+            Pos::new(3.0, 1.0).rotate_around(pivot, FRAC_PI_2),
+            Pos::new(1.0, 3.0),
+        );
+    }
+
+    #[test]
+    fn scale_around() {
+        let pivot = Pos::new(1.0, 1.0);
+
+        assert_eq!(
+            Pos::new(3.0, 2.0).scale_around(pivot, 2.0),
+            Pos::new(5.0, 3.0)
+        );
+        assert_eq!(
+            Pos::new(3.0, 2.0).scale_around(pivot, 1.0),
+            Pos::new(3.0, 2.0)
+        );
+    }
+
+    #[test]
+    fn midpoint() {
+        assert_eq!(
+            Pos::new(0.0, 2.0).midpoint(Pos::new(4.0, -2.0)),
+            Pos::new(2.0, 0.0)
+        );
+    }
+}
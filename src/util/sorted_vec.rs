@@ -0,0 +1,214 @@
+use std::{cmp::Ordering, ops::Deref, slice};
+
+use crate::section::timing_points::{DifficultyPoint, EffectPoint, SamplePoint, TimingPoint};
+
+/// A total order used by [`SortedVec`].
+///
+/// Implemented for all [`Ord`] types as well as for control points, which are
+/// ordered by their time through [`f64::total_cmp`].
+pub trait SortedVecOrd {
+    /// Compare two elements.
+    fn sorted_cmp(&self, other: &Self) -> Ordering;
+}
+
+impl<T: Ord> SortedVecOrd for T {
+    fn sorted_cmp(&self, other: &Self) -> Ordering {
+        self.cmp(other)
+    }
+}
+
+macro_rules! impl_sorted_vec_ord_by_time {
+    ( $( $ty:ty ),* ) => {
+        $(
+            impl SortedVecOrd for $ty {
+                fn sorted_cmp(&self, other: &Self) -> Ordering {
+                    self.time.total_cmp(&other.time)
+                }
+            }
+        )*
+    };
+}
+
+impl_sorted_vec_ord_by_time!(DifficultyPoint, EffectPoint, SamplePoint, TimingPoint);
+
+/// A [`Vec`] whose elements are always sorted.
+///
+/// Elements are compared through [`SortedVecOrd`] which is a total order so
+/// the sorting holds for any input, including NaN times.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SortedVec<T> {
+    inner: Vec<T>,
+}
+
+impl<T> SortedVec<T> {
+    /// Create a new empty [`SortedVec`].
+    pub const fn new() -> Self {
+        Self { inner: Vec::new() }
+    }
+
+    /// Create a new empty [`SortedVec`] with the given capacity.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            inner: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// The sorted elements as a slice.
+    pub fn as_slice(&self) -> &[T] {
+        &self.inner
+    }
+
+    /// Remove and return the element at the given index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is out of bounds.
+    pub fn remove(&mut self, idx: usize) -> T {
+        self.inner.remove(idx)
+    }
+
+    /// Remove all elements.
+    pub fn clear(&mut self) {
+        self.inner.clear();
+    }
+
+    /// Consume the [`SortedVec`] and return the inner [`Vec`].
+    pub fn into_inner(self) -> Vec<T> {
+        self.inner
+    }
+}
+
+impl<T: SortedVecOrd> SortedVec<T> {
+    /// Insert an element while maintaining the sort order and return its
+    /// index.
+    ///
+    /// Elements that compare equal keep their insertion order.
+    pub fn insert(&mut self, value: T) -> usize {
+        let idx = self
+            .inner
+            .partition_point(|probe| probe.sorted_cmp(&value) != Ordering::Greater);
+
+        self.inner.insert(idx, value);
+
+        idx
+    }
+
+    /// Binary search for the given element.
+    ///
+    /// See [`slice::binary_search`].
+    pub fn binary_search(&self, value: &T) -> Result<usize, usize> {
+        self.inner.binary_search_by(|probe| probe.sorted_cmp(value))
+    }
+}
+
+impl<T> Default for SortedVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Deref for SortedVec<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T: SortedVecOrd> From<Vec<T>> for SortedVec<T> {
+    fn from(mut inner: Vec<T>) -> Self {
+        inner.sort_by(SortedVecOrd::sorted_cmp);
+
+        Self { inner }
+    }
+}
+
+impl<T: SortedVecOrd> FromIterator<T> for SortedVec<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::from(iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+impl<T: SortedVecOrd> Extend<T> for SortedVec<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.insert(value);
+        }
+    }
+}
+
+impl<T> IntoIterator for SortedVec<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.inner.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a SortedVec<T> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.inner.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::section::timing_points::DifficultyPoint;
+
+    use super::*;
+
+    #[test]
+    fn insert_out_of_order() {
+        let mut vec = SortedVec::new();
+
+        assert_eq!(vec.insert(5), 0);
+        assert_eq!(vec.insert(1), 0);
+        assert_eq!(vec.insert(3), 1);
+        assert_eq!(vec.insert(9), 3);
+
+        assert_eq!(vec.as_slice(), [1, 3, 5, 9]);
+        assert_eq!(vec.binary_search(&5), Ok(2));
+        assert_eq!(vec.binary_search(&4), Err(2));
+    }
+
+    #[test]
+    fn control_points() {
+        let mut vec = SortedVec::new();
+        vec.insert(DifficultyPoint::new(2000.0, -50.0, 2.0));
+        vec.insert(DifficultyPoint::new(1000.0, -100.0, 1.0));
+        vec.insert(DifficultyPoint::new(2000.0, -25.0, 4.0));
+
+        let times: Vec<_> = vec.iter().map(|point| point.time).collect();
+        assert_eq!(times, [1000.0, 2000.0, 2000.0]);
+
+        // equal elements keep their insertion order
+        assert!((vec[1].slider_velocity - 2.0).abs() < f64::EPSILON);
+        assert!((vec[2].slider_velocity - 4.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn nan_times() {
+        let vec: SortedVec<_> = [3000.0, f64::NAN, 1000.0, 2000.0]
+            .into_iter()
+            .map(|time| DifficultyPoint::new(time, -100.0, 1.0))
+            .collect();
+
+        let times: Vec<_> = vec.iter().map(|point| point.time).collect();
+        assert_eq!(times[..3], [1000.0, 2000.0, 3000.0]);
+        assert!(times[3].is_nan());
+
+        let mut vec = SortedVec::new();
+
+        for time in [f64::NAN, 2000.0, f64::NAN, 1000.0] {
+            vec.insert(DifficultyPoint::new(time, -100.0, 1.0));
+        }
+
+        let times: Vec<_> = vec.iter().map(|point| point.time).collect();
+        assert_eq!(times[..2], [1000.0, 2000.0]);
+        assert!(times[2..].iter().all(|time| time.is_nan()));
+    }
+}
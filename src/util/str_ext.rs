@@ -14,8 +14,19 @@ pub trait StrExt {
     fn parse_with_limits<N: ParseNumber>(&self, limit: N) -> Result<N, ParseNumberError>;
 
     /// Replace windows path separators with unix ones.
+    ///
+    /// Decoding uses this for filenames such as the audio file or the
+    /// background file so that they are stored with `/` as separator.
     fn to_standardized_path(&self) -> String;
 
+    /// Replace unix path separators with windows ones.
+    ///
+    /// This is the inverse of [`StrExt::to_standardized_path`] and is used
+    /// when encoding filenames so that they match what osu! writes.
+    fn to_osu_path(&self) -> String {
+        self.to_standardized_path().replace('/', "\\")
+    }
+
     /// Fix path and quotation segments to normalize filenames.
     fn clean_filename(&self) -> String;
 }
@@ -43,3 +54,20 @@ impl StrExt for str {
             .to_standardized_path()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn path_separators() {
+        let path = "folder\\sub/song.mp3";
+
+        let standardized = path.to_standardized_path();
+        assert_eq!(standardized, "folder/sub/song.mp3");
+
+        let osu_path = standardized.to_osu_path();
+        assert_eq!(osu_path, "folder\\sub\\song.mp3");
+        assert_eq!(osu_path.to_standardized_path(), standardized);
+    }
+}
@@ -0,0 +1,96 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+use rosu_map::{
+    section::hit_objects::{CurveBuffers, HitObjectKind},
+    Beatmap,
+};
+
+/// Counts the allocations of the current thread so that tests running in
+/// parallel don't interfere with each other.
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn count_allocations(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    f();
+
+    ALLOCATIONS.with(Cell::get) - before
+}
+
+fn clear_curves(map: &mut Beatmap) {
+    for h in map.hit_objects.iter_mut() {
+        if let HitObjectKind::Slider(ref mut slider) = h.kind {
+            slider.path.clear_curve();
+        }
+    }
+}
+
+#[test]
+fn end_time_of_reuses_buffers() {
+    let mut map = Beatmap::from_path("./resources/Soleily - Renatus (Gamu) [Insane].osu").unwrap();
+    let len = map.hit_objects.len();
+    let mut bufs = CurveBuffers::default();
+
+    // Grow the buffers to the size that the longest slider requires
+    for i in 0..len {
+        map.end_time_of(i, &mut bufs);
+    }
+
+    // Cached curves don't require anything
+    let cached = count_allocations(|| {
+        for i in 0..len {
+            map.end_time_of(i, &mut bufs);
+        }
+    });
+
+    assert_eq!(cached, 0);
+
+    // Re-calculated curves only allocate to store the curve itself
+    clear_curves(&mut map);
+
+    let reused = count_allocations(|| {
+        for i in 0..len {
+            map.end_time_of(i, &mut bufs);
+        }
+    });
+
+    clear_curves(&mut map);
+
+    let fresh = count_allocations(|| {
+        for i in 0..len {
+            map.end_time_of(i, &mut CurveBuffers::default());
+        }
+    });
+
+    assert!(
+        reused < fresh,
+        "re-used buffers: {reused} allocations, fresh buffers: {fresh} allocations"
+    );
+}
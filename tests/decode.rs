@@ -1,22 +1,28 @@
+use std::collections::HashSet;
+
 use rosu_map::{
     section::{
         colors::{Color, Colors},
         difficulty::Difficulty,
         editor::Editor,
-        events::{BreakPeriod, Events},
-        general::{CountdownType, GameMode, General},
+        events::{BreakPeriod, EventType, Events, StoryboardFileRef},
+        general::{CountdownType, GameMode, General, ParseGameModeError},
         hit_objects::{
-            hit_samples::{HitSampleInfo, SampleBank},
-            CurveBuffers, HitObject, HitObjectKind, HitObjects, PathType,
+            hit_samples::{HitSampleInfo, HitSoundType, SampleBank, SampleBankInfo},
+            CurveBuffers, HitObject, HitObjectCircle, HitObjectKind, HitObjectSlider, HitObjects,
+            InvalidComboOffsetError, PathControlPoint, PathType, SliderEvent, SliderEventType,
+            SliderPath,
         },
         metadata::Metadata,
+        summary::BeatmapSummary,
         timing_points::{
-            ControlPoints, DifficultyPoint, EffectPoint, SamplePoint, TimeSignature, TimingPoint,
-            TimingPoints,
+            ControlPointRef, ControlPoints, DifficultyPoint, EffectPoint, SamplePoint,
+            TimeSignature, TimingPoint, TimingPoints,
         },
+        Section,
     },
     util::Pos,
-    Beatmap,
+    Beatmap, DecodeBeatmap, DecodeOptions, DecodeState,
 };
 use test_log::test;
 
@@ -39,10 +45,10 @@ fn general() {
     assert_eq!(general.preview_time, 164471);
     assert_eq!(general.stack_leniency, 0.7);
     assert_eq!(general.mode, GameMode::Osu);
-    assert_eq!(general.letterbox_in_breaks, false);
-    assert_eq!(general.special_style, false);
-    assert_eq!(general.widescreen_storyboard, false);
-    assert_eq!(general.samples_match_playback_rate, false);
+    assert!(!general.letterbox_in_breaks);
+    assert!(!general.special_style);
+    assert!(!general.widescreen_storyboard);
+    assert!(!general.samples_match_playback_rate);
     assert_eq!(general.countdown, CountdownType::None);
     assert_eq!(general.countdown_offset, 0);
 }
@@ -63,6 +69,18 @@ fn editor() {
     assert_eq!(editor.timeline_zoom, 2.0);
 }
 
+#[test]
+fn lenient_bookmarks() {
+    let content = "osu file format v14
+
+[Editor]
+Bookmarks: 100,50.5,,200,abc
+";
+
+    let editor: Editor = rosu_map::from_str(content).unwrap();
+    assert_eq!(editor.bookmarks, [50, 100, 200]);
+}
+
 #[test]
 fn metadata() {
     let metadata: Metadata = rosu_map::from_str(RENATUS).unwrap();
@@ -148,7 +166,7 @@ fn timing_points() {
         timing_point.time_signature,
         TimeSignature::new_simple_quadruple()
     );
-    assert_eq!(timing_point.omit_first_bar_line, false);
+    assert!(!timing_point.omit_first_bar_line);
 
     let timing_point = control_points
         .timing_point_at(48428.0)
@@ -159,7 +177,7 @@ fn timing_points() {
         timing_point.time_signature,
         TimeSignature::new_simple_quadruple()
     );
-    assert_eq!(timing_point.omit_first_bar_line, false);
+    assert!(!timing_point.omit_first_bar_line);
 
     let timing_point = control_points
         .timing_point_at(119637.0)
@@ -170,7 +188,7 @@ fn timing_points() {
         timing_point.time_signature,
         TimeSignature::new_simple_quadruple()
     );
-    assert_eq!(timing_point.omit_first_bar_line, false);
+    assert!(!timing_point.omit_first_bar_line);
 
     let difficulty_point = control_points
         .difficulty_point_at(0.0)
@@ -215,19 +233,19 @@ fn timing_points() {
         .effect_point_at(0.0)
         .map_or_else(EffectPoint::default, EffectPoint::clone);
     assert_eq!(effect_point.time, 0.0);
-    assert_eq!(effect_point.kiai, false);
+    assert!(!effect_point.kiai);
 
     let effect_point = control_points
         .effect_point_at(53703.0)
         .map_or_else(EffectPoint::default, EffectPoint::clone);
     assert_eq!(effect_point.time, 53703.0);
-    assert_eq!(effect_point.kiai, true);
+    assert!(effect_point.kiai);
 
     let effect_point = control_points
         .effect_point_at(116637.0)
         .map_or_else(EffectPoint::default, EffectPoint::clone);
     assert_eq!(effect_point.time, 95901.0);
-    assert_eq!(effect_point.kiai, false);
+    assert!(!effect_point.kiai);
 }
 
 #[test]
@@ -273,10 +291,10 @@ fn overlapping_timing_points() {
     assert!((slider_velocity_at(&control_points, 2500.0) - 0.75).abs() <= 0.1);
     assert!((slider_velocity_at(&control_points, 3500.0) - 1.5).abs() <= 0.1);
 
-    assert_eq!(kiai_at(&control_points, 500.0), true);
-    assert_eq!(kiai_at(&control_points, 1500.0), true);
-    assert_eq!(kiai_at(&control_points, 2500.0), false);
-    assert_eq!(kiai_at(&control_points, 3500.0), true);
+    assert!(kiai_at(&control_points, 500.0));
+    assert!(kiai_at(&control_points, 1500.0));
+    assert!(!kiai_at(&control_points, 2500.0));
+    assert!(kiai_at(&control_points, 3500.0));
 
     assert_eq!(sample_bank_at(&control_points, 500.0), SampleBank::Drum);
     assert_eq!(sample_bank_at(&control_points, 1500.0), SampleBank::Drum);
@@ -307,12 +325,30 @@ fn omit_bar_line_effect() {
     assert_eq!(control_points.timing_points.len(), 6);
     assert_eq!(control_points.effect_points.len(), 0);
 
-    assert_eq!(omit_first_bar_line_at(&control_points, 500.0), false);
-    assert_eq!(omit_first_bar_line_at(&control_points, 1500.0), true);
-    assert_eq!(omit_first_bar_line_at(&control_points, 2500.0), false);
-    assert_eq!(omit_first_bar_line_at(&control_points, 3500.0), false);
-    assert_eq!(omit_first_bar_line_at(&control_points, 4500.0), false);
-    assert_eq!(omit_first_bar_line_at(&control_points, 5500.0), true);
+    assert!(!omit_first_bar_line_at(&control_points, 500.0));
+    assert!(omit_first_bar_line_at(&control_points, 1500.0));
+    assert!(!omit_first_bar_line_at(&control_points, 2500.0));
+    assert!(!omit_first_bar_line_at(&control_points, 3500.0));
+    assert!(!omit_first_bar_line_at(&control_points, 4500.0));
+    assert!(omit_first_bar_line_at(&control_points, 5500.0));
+}
+
+#[test]
+fn omits_first_bar_line_at() {
+    let control_points =
+        rosu_map::from_path::<TimingPoints>("./resources/omit-barline-control-points.osu")
+            .unwrap()
+            .control_points;
+
+    assert!(!control_points.omits_first_bar_line_at(500.0));
+    assert!(control_points.omits_first_bar_line_at(1500.0));
+    assert!(!control_points.omits_first_bar_line_at(2500.0));
+    assert!(!control_points.omits_first_bar_line_at(3500.0));
+    assert!(!control_points.omits_first_bar_line_at(4500.0));
+    assert!(control_points.omits_first_bar_line_at(5500.0));
+
+    // Without any timing points
+    assert!(!ControlPoints::default().omits_first_bar_line_at(0.0));
 }
 
 #[test]
@@ -760,12 +796,12 @@ fn multi_segment_sliders() {
 
 #[test]
 fn slider_len_extension_edge_case() {
-    let hit_objects =
+    let mut hit_objects =
         rosu_map::from_path::<HitObjects>("./resources/duplicate-last-position-slider.osu")
             .unwrap()
             .hit_objects;
 
-    let HitObjectKind::Slider(ref slider) = hit_objects[0].kind else {
+    let HitObjectKind::Slider(ref mut slider) = hit_objects[0].kind else {
         panic!("Expected slider")
     };
 
@@ -773,6 +809,8 @@ fn slider_len_extension_edge_case() {
 
     let mut bufs = CurveBuffers::default();
     assert_eq!(slider.path.borrowed_curve(&mut bufs).dist(), 1.0);
+    assert_eq!(slider.path.expected_or_curve_dist(&mut bufs), 2.0);
+    assert_eq!(slider.path.dist(), 1.0);
 }
 
 #[test]
@@ -878,8 +916,8 @@ fn nan_control_points() {
     assert_eq!(slider_velocity_at(&control_points, 2000.0), 1.0);
     assert_eq!(slider_velocity_at(&control_points, 3000.0), 1.0);
 
-    assert_eq!(generate_ticks_at(&control_points, 2000.0), false);
-    assert_eq!(generate_ticks_at(&control_points, 3000.0), true);
+    assert!(!generate_ticks_at(&control_points, 2000.0));
+    assert!(generate_ticks_at(&control_points, 3000.0));
 }
 
 #[test]
@@ -905,3 +943,1420 @@ fn new_combo_after_break() {
     assert!(hit_objects[1].new_combo());
     assert!(!hit_objects[2].new_combo());
 }
+
+#[test]
+fn no_trailing_newline() {
+    let content = "osu file format v14
+
+[HitObjects]
+256,192,1000,1,0,0:0:0:0:
+128,64,2000,1,0,0:0:0:0:";
+
+    let hit_objects = rosu_map::from_str::<HitObjects>(content)
+        .unwrap()
+        .hit_objects;
+
+    assert_eq!(hit_objects.len(), 2);
+    assert_eq!(hit_objects[1].start_time, 2000.0);
+
+    let HitObjectKind::Circle(ref circle) = hit_objects[1].kind else {
+        panic!("Expected a circle")
+    };
+
+    assert_eq!(circle.pos, Pos::new(128.0, 64.0));
+}
+
+#[test]
+fn objects_at_time() {
+    let map = Beatmap::from_path("./resources/sample-beatmap-mania.osu").unwrap();
+
+    let chord = map.objects_at_time(2318.0);
+    assert_eq!(chord.len(), 5);
+    assert!(chord.iter().all(|h| h.start_time == 2318.0));
+
+    assert_eq!(map.objects_at_time(2318.05).len(), 5);
+    assert_eq!(map.objects_at_time(2317.95).len(), 5);
+    assert!(map.objects_at_time(2318.5).is_empty());
+
+    assert_eq!(map.objects_at_time(200.0).len(), 1);
+    assert!(map.objects_at_time(201.0).is_empty());
+}
+
+#[test]
+fn object_value_sum() {
+    let content = "osu file format v14
+
+[Difficulty]
+OverallDifficulty:5
+SliderMultiplier:1
+SliderTickRate:1
+
+[TimingPoints]
+0,1000,4,1,0,100,1,0
+
+[HitObjects]
+256,192,1000,1,0,0:0:0:0:
+0,192,2000,2,0,L|200:192,1,200
+256,192,5000,12,0,6000,0:0:0:0:";
+
+    let mut map: Beatmap = content.parse().unwrap();
+
+    // circle: 300
+    // slider: 300 + 30 (head) + 10 (tick) + 30 (tail)
+    // spinner: 300 + 4 * 100 (spins) + 3 * 1100 (bonus spins)
+    assert_eq!(map.object_value_sum(), 300 + 370 + 4000);
+}
+
+#[test]
+fn slider_repeat_times() {
+    let content = "osu file format v14
+
+[Difficulty]
+SliderMultiplier:1
+
+[TimingPoints]
+0,1000,4,1,0,100,1,0
+
+[HitObjects]
+0,192,1000,2,0,L|100:192,3,100";
+
+    let mut hit_objects = rosu_map::from_str::<HitObjects>(content)
+        .unwrap()
+        .hit_objects;
+
+    let start_time = hit_objects[0].start_time;
+
+    let HitObjectKind::Slider(ref mut slider) = hit_objects[0].kind else {
+        panic!("Expected a slider")
+    };
+
+    let repeat_times = slider.repeat_times(start_time, &mut CurveBuffers::default());
+    assert_eq!(repeat_times, [2000.0, 3000.0]);
+}
+
+#[test]
+fn set_slider_velocity() {
+    let content = "osu file format v14
+
+[Difficulty]
+SliderMultiplier:1
+
+[TimingPoints]
+0,1000,4,1,0,100,1,0
+
+[HitObjects]
+0,192,1000,2,0,L|100:192,1,100
+0,192,3000,2,0,L|100:192,1,100";
+
+    let mut map: Beatmap = content.parse().unwrap();
+
+    assert_eq!(map.hit_objects[0].end_time(), 2000.0);
+    assert_eq!(map.hit_objects[1].end_time(), 4000.0);
+
+    map.set_slider_velocity(2500.0, 2.0);
+
+    assert_eq!(map.hit_objects[0].end_time(), 2000.0);
+    assert_eq!(map.hit_objects[1].end_time(), 3500.0);
+    assert_eq!(map.control_points.difficulty_points.len(), 1);
+}
+
+#[test]
+fn combo_color_skips() {
+    let map = Beatmap::from_path("./resources/hitobject-combo-offset.osu").unwrap();
+
+    assert_eq!(
+        map.combo_color_skips(),
+        [1, 1, 2, 2, 3, 3, 4, 4, 8, 8, 8, 9]
+    );
+}
+
+#[test]
+fn bpm_changes() {
+    let content = "osu file format v14
+
+[TimingPoints]
+0,500,4,2,0,100,1,0
+1000,500,4,2,0,100,1,0
+1500,-50,4,2,0,100,0,0
+2000,250,4,2,0,100,1,0
+";
+
+    let control_points = rosu_map::from_str::<TimingPoints>(content)
+        .unwrap()
+        .control_points;
+
+    assert_eq!(control_points.timing_points.len(), 3);
+    assert_eq!(
+        control_points.bpm_changes(),
+        vec![(0.0, 120.0), (2000.0, 240.0)]
+    );
+}
+
+#[test]
+fn approx_eq() {
+    let map = RENATUS.parse::<Beatmap>().unwrap();
+    let mut other = map.clone();
+    other.control_points.timing_points[0].beat_len += 1e-9;
+
+    assert_ne!(map, other);
+    assert!(map.approx_eq(&other, 1e-6));
+    assert!(!map.approx_eq(&other, 1e-12));
+}
+
+#[test]
+fn dedup_hit_objects() {
+    let content = "osu file format v14
+
+[HitObjects]
+256,192,1000,1,0,0:0:0:0:
+256,192,1000,1,0,0:0:0:0:
+256,192,2000,1,0,0:0:0:0:
+";
+
+    let hit_objects = rosu_map::from_str::<HitObjects>(content)
+        .unwrap()
+        .hit_objects;
+
+    assert_eq!(hit_objects.len(), 3);
+
+    let options = DecodeOptions {
+        dedup_hit_objects: true,
+    };
+
+    let hit_objects = HitObjects::decode_with_options(content.as_bytes(), &options)
+        .unwrap()
+        .hit_objects;
+
+    let start_times: Vec<_> = hit_objects.iter().map(|h| h.start_time).collect();
+    assert_eq!(start_times, [1000.0, 2000.0]);
+}
+
+#[test]
+fn playfield_size() {
+    let map = RENATUS.parse::<Beatmap>().unwrap();
+    assert_eq!(map.playfield_size(), (512.0, 384.0));
+
+    let map = Beatmap::from_path("./resources/sample-beatmap-mania.osu").unwrap();
+    assert_eq!(map.playfield_size(), (5.0 * 80.0, 384.0));
+}
+
+#[test]
+fn truncate() {
+    let mut map = RENATUS.parse::<Beatmap>().unwrap();
+    let sample_point = map.sample_point_at(60_000.0).cloned();
+    map.truncate(60_000.0);
+
+    assert_eq!(map.hit_objects.len(), 295);
+    assert!(map.hit_objects.iter().all(|h| h.start_time <= 60_000.0));
+    assert_eq!(map.control_points.timing_points.len(), 1);
+    assert_eq!(map.control_points.difficulty_points.len(), 0);
+    assert_eq!(map.control_points.effect_points.len(), 1);
+    assert_eq!(map.control_points.sample_points.len(), 7);
+    assert_eq!(map.sample_point_at(60_000.0).cloned(), sample_point);
+}
+
+#[test]
+fn decode_with_hook() {
+    let content = "osu file format v14
+
+[General]
+Mode: 9
+
+[Difficulty]
+CircleSize: 4
+OverallDifficulty: abc
+
+[TimingPoints]
+0,500,4,2,0,100,1,0
+invalid
+
+[HitObjects]
+256,192,1000,1,0,0:0:0:0:
+256,192
+";
+
+    let mut sections = Vec::new();
+
+    let map = Beatmap::decode_with_hook(content.as_bytes(), |section, _, _| {
+        sections.push(*section);
+    })
+    .unwrap();
+
+    assert_eq!(map.hit_objects.len(), 1);
+    assert_eq!(
+        sections,
+        [
+            Section::General,
+            Section::Difficulty,
+            Section::TimingPoints,
+            Section::HitObjects
+        ]
+    );
+}
+
+#[test]
+fn snap_time() {
+    let map = RENATUS.parse::<Beatmap>().unwrap();
+    let expected = 956.0 + 10.0 * 329.670_329_670_33 / 4.0;
+
+    assert!((map.snap_time(1783.0) - expected).abs() < 1e-9);
+    assert!((map.control_points.snap_time(1783.0, 4) - expected).abs() < 1e-9);
+    assert!(
+        (map.control_points.snap_time(1783.0, 1) - (956.0 + 3.0 * 329.670_329_670_33)).abs() < 1e-9
+    );
+    assert!((map.control_points.snap_time(1783.0, 0) - 1783.0).abs() < f64::EPSILON);
+}
+
+#[test]
+fn background_colors() {
+    let content = "osu file format v14
+
+[Events]
+0,0,\"bg.jpg\",0,0
+3,100,163,162,255
+3,5000,0,0,0
+";
+
+    let events = rosu_map::from_str::<Events>(content).unwrap();
+
+    assert_eq!(
+        events.background_colors,
+        [
+            (100.0, Color::new(163, 162, 255, 255)),
+            (5000.0, Color::new(0, 0, 0, 255))
+        ]
+    );
+
+    let mut map = content.parse::<Beatmap>().unwrap();
+    let encoded = map.encode_to_string().unwrap();
+    let decoded = encoded.parse::<Beatmap>().unwrap();
+
+    assert_eq!(decoded.background_colors, events.background_colors);
+}
+
+#[test]
+fn validate_slider_paths() {
+    let content = "osu file format v14
+
+[HitObjects]
+100,100,1000,2,0,L|200:100,1,100
+100,100,2000,1,0,0:0:0:0:
+100,100,3000,2,0,L|100:100,1,0
+";
+
+    let mut map = content.parse::<Beatmap>().unwrap();
+
+    assert_eq!(map.validate_slider_paths(&mut CurveBuffers::default()), [2]);
+}
+
+#[test]
+fn into_and_from_sections() {
+    let map = RENATUS.parse::<Beatmap>().unwrap();
+    let sections = map.clone().into_sections();
+
+    assert_eq!(sections.0.audio_file, map.audio_file);
+    assert_eq!(sections.5.audio_file, map.audio_file);
+    assert_eq!(sections.7.len(), map.hit_objects.len());
+
+    assert_eq!(Beatmap::from_sections(sections), map);
+}
+
+#[test]
+fn orphan_difficulty_points() {
+    let content = "osu file format v14
+
+[TimingPoints]
+500,-50,4,2,0,100,0,0
+1000,500,4,2,0,100,1,0
+2000,-50,4,2,0,100,0,0
+";
+
+    let control_points = rosu_map::from_str::<TimingPoints>(content)
+        .unwrap()
+        .control_points;
+
+    assert!(control_points.has_orphan_difficulty_points());
+
+    let control_points = rosu_map::from_str::<TimingPoints>(RENATUS)
+        .unwrap()
+        .control_points;
+
+    assert!(!control_points.has_orphan_difficulty_points());
+}
+
+#[test]
+fn hit_objects_mut_sorts() {
+    let mut map = RENATUS.parse::<Beatmap>().unwrap();
+
+    {
+        let mut hit_objects = map.hit_objects_mut();
+        hit_objects[0].start_time = 1_000_000.0;
+        hit_objects.push(HitObject::circle(Pos::new(0.0, 0.0), 0.0));
+    }
+
+    assert!(map
+        .hit_objects
+        .windows(2)
+        .all(|w| w[0].start_time <= w[1].start_time));
+    assert!(map.hit_objects[0].start_time.abs() < f64::EPSILON);
+    assert!((map.hit_objects.last().unwrap().start_time - 1_000_000.0).abs() < f64::EPSILON);
+}
+
+#[test]
+fn preview_time_timestamp() {
+    let millis = rosu_map::from_str::<General>("[General]\nPreviewTime: 164471").unwrap();
+    let timestamp = rosu_map::from_str::<General>("[General]\nPreviewTime: 02:44:471").unwrap();
+
+    assert_eq!(millis.preview_time, 164_471);
+    assert_eq!(timestamp.preview_time, millis.preview_time);
+}
+
+#[test]
+fn slider_legacy_last_tick_time() {
+    let content = "osu file format v14
+
+[Difficulty]
+SliderMultiplier:1
+
+[TimingPoints]
+0,1000,4,1,0,100,1,0
+
+[HitObjects]
+0,192,1000,2,0,L|100:192,1,100
+0,192,3000,2,0,L|5:192,1,5";
+
+    let mut hit_objects = rosu_map::from_str::<HitObjects>(content)
+        .unwrap()
+        .hit_objects;
+
+    let mut bufs = CurveBuffers::default();
+
+    let mut last_tick_times = hit_objects.iter_mut().map(|h| {
+        let start_time = h.start_time;
+
+        let HitObjectKind::Slider(ref mut slider) = h.kind else {
+            panic!("Expected a slider")
+        };
+
+        slider.legacy_last_tick_time(start_time, &mut bufs)
+    });
+
+    // Long enough slider
+    let end_time = 2000.0;
+    let expected = end_time - HitObjectSlider::LEGACY_LAST_TICK_OFFSET;
+    assert!((last_tick_times.next().unwrap() - expected).abs() < 1e-9);
+
+    // Short slider is clamped to its halfway point
+    let expected = 3000.0 + 50.0 / 2.0;
+    assert!((last_tick_times.next().unwrap() - expected).abs() < 1e-9);
+}
+
+#[test]
+fn hold_end_before_start() {
+    let content = "osu file format v14
+
+[General]
+Mode: 3
+
+[HitObjects]
+64,192,1000,128,0,500:0:0:0:0:
+192,192,2000,128,0,2500:0:0:0:0:
+";
+
+    let mut errors = Vec::new();
+
+    let hit_objects = HitObjects::decode_with_hook(content.as_bytes(), |_, _, err| {
+        errors.push(err.to_string());
+    })
+    .unwrap()
+    .hit_objects;
+
+    let durations: Vec<_> = hit_objects
+        .iter()
+        .map(|h| match h.kind {
+            HitObjectKind::Hold(ref h) => h.duration,
+            _ => panic!("Expected a hold note"),
+        })
+        .collect();
+
+    // Clamping the duration is only a warning so the line doesn't fail
+    assert_eq!(durations, [0.0, 500.0]);
+    assert!(errors.is_empty(), "{errors:?}");
+}
+
+#[test]
+fn slider_render_path() {
+    let content = "osu file format v14
+
+[HitObjects]
+0,0,1000,2,0,B|100:200|200:0,1,300
+0,0,2000,2,0,P|100:100|200:0,1,300";
+
+    let mut hit_objects = rosu_map::from_str::<HitObjects>(content)
+        .unwrap()
+        .hit_objects;
+
+    for h in hit_objects.iter_mut() {
+        let HitObjectKind::Slider(ref mut slider) = h.kind else {
+            panic!("Expected a slider")
+        };
+
+        let default_len = slider.path.curve().path().len();
+
+        let mut bufs = CurveBuffers::default();
+        bufs.set_bezier_tolerance(Some(0.01));
+        bufs.set_circular_arc_tolerance(Some(0.01));
+
+        let detailed = slider.render_path(&mut bufs);
+
+        assert!(detailed.len() > default_len);
+
+        // The stored curve keeps its default fidelity
+        assert_eq!(slider.path.curve().path().len(), default_len);
+    }
+}
+
+#[test]
+fn custom_decode_state() {
+    struct FormatVersion {
+        version: i32,
+    }
+
+    struct FormatVersionState {
+        version: i32,
+    }
+
+    impl DecodeState for FormatVersionState {
+        fn create(version: i32) -> Self {
+            Self { version }
+        }
+    }
+
+    impl From<FormatVersionState> for FormatVersion {
+        fn from(state: FormatVersionState) -> Self {
+            Self {
+                version: state.version,
+            }
+        }
+    }
+
+    impl DecodeBeatmap for FormatVersion {
+        type State = FormatVersionState;
+        type Error = std::convert::Infallible;
+
+        fn parse_general(_: &mut Self::State, _: &str) -> Result<(), Self::Error> {
+            Ok(())
+        }
+        fn parse_editor(_: &mut Self::State, _: &str) -> Result<(), Self::Error> {
+            Ok(())
+        }
+        fn parse_metadata(_: &mut Self::State, _: &str) -> Result<(), Self::Error> {
+            Ok(())
+        }
+        fn parse_difficulty(_: &mut Self::State, _: &str) -> Result<(), Self::Error> {
+            Ok(())
+        }
+        fn parse_events(_: &mut Self::State, _: &str) -> Result<(), Self::Error> {
+            Ok(())
+        }
+        fn parse_timing_points(_: &mut Self::State, _: &str) -> Result<(), Self::Error> {
+            Ok(())
+        }
+        fn parse_colors(_: &mut Self::State, _: &str) -> Result<(), Self::Error> {
+            Ok(())
+        }
+        fn parse_hit_objects(_: &mut Self::State, _: &str) -> Result<(), Self::Error> {
+            Ok(())
+        }
+        fn parse_variables(_: &mut Self::State, _: &str) -> Result<(), Self::Error> {
+            Ok(())
+        }
+        fn parse_catch_the_beat(_: &mut Self::State, _: &str) -> Result<(), Self::Error> {
+            Ok(())
+        }
+        fn parse_mania(_: &mut Self::State, _: &str) -> Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    let decoded = rosu_map::from_str::<FormatVersion>(RENATUS).unwrap();
+    assert_eq!(decoded.version, 14);
+
+    let decoded = rosu_map::from_str::<FormatVersion>("osu file format v5\n").unwrap();
+    assert_eq!(decoded.version, 5);
+}
+
+#[test]
+fn total_break_time() {
+    let content = "osu file format v14
+
+[Events]
+2,1000,3000
+2,5000,5500
+2,8000,12000";
+
+    let map = content.parse::<Beatmap>().unwrap();
+
+    assert!((map.total_break_time(false) - 6500.0).abs() < f64::EPSILON);
+    assert!((map.total_break_time(true) - 6000.0).abs() < f64::EPSILON);
+}
+
+#[test]
+fn sample_bank_info_new() {
+    let bank_info = SampleBankInfo::new(SampleBank::Drum, SampleBank::Soft, 0, 70, None);
+
+    let sound_type = HitSoundType::from(HitSoundType::NORMAL | HitSoundType::CLAP);
+    let samples = bank_info.convert_sound_type(sound_type);
+
+    let expected = vec![
+        HitSampleInfo::new(HitSampleInfo::HIT_NORMAL, Some(SampleBank::Drum), 0, 70),
+        HitSampleInfo::new(HitSampleInfo::HIT_CLAP, Some(SampleBank::Soft), 0, 70),
+    ];
+
+    assert_eq!(samples, expected);
+
+    // The addition bank falls back to the normal bank
+    let bank_info = SampleBankInfo::new(SampleBank::Drum, SampleBank::None, 0, 70, None);
+    assert_eq!(bank_info.bank_for_addition, Some(SampleBank::Drum));
+}
+
+#[test]
+fn display_bpm() {
+    let content = "osu file format v14
+
+[TimingPoints]
+0,500,4,2,0,100,1,0
+1000,-50,4,2,0,100,0,0
+2000,333.333,4,2,0,100,1,0
+";
+
+    let map = content.parse::<Beatmap>().unwrap();
+    assert_eq!(map.display_bpm(), "120-180");
+
+    let map = RENATUS.parse::<Beatmap>().unwrap();
+    assert_eq!(map.display_bpm(), "91-182");
+
+    assert_eq!(Beatmap::default().display_bpm(), "60");
+}
+
+#[test]
+fn bpm() {
+    let point = TimingPoint::from_bpm(0.0, 150.0, false, TimeSignature::new_simple_quadruple());
+    assert!((point.beat_len - 400.0).abs() < f64::EPSILON);
+    assert!((point.bpm() - 150.0).abs() < f64::EPSILON);
+
+    let content = "osu file format v14
+
+[TimingPoints]
+1000,250,4,2,0,100,1,0
+2000,500,4,2,0,100,1,0
+4000,-50,4,2,0,100,0,0
+5000,250,4,2,0,100,1,0
+
+[HitObjects]
+256,192,1000,1,0,0:0:0:0:
+256,192,8000,1,0,0:0:0:0:
+";
+
+    let mut map = content.parse::<Beatmap>().unwrap();
+    assert_eq!(map.bpm_range(), (120.0, 240.0));
+
+    // 240 BPM from 0 to 2000 and 5000 to 8000, 120 BPM in between
+    assert!((map.most_common_bpm() - 240.0).abs() < f64::EPSILON);
+    assert_eq!(map.nth_most_common_bpm(1), Some(120.0));
+    assert_eq!(map.nth_most_common_bpm(2), None);
+    assert_eq!(map.bpm_histogram(), [(240.0, 5000.0), (120.0, 3000.0)]);
+    assert_eq!(
+        map.bpm_timeline(),
+        [(1000.0, 240.0), (2000.0, 120.0), (5000.0, 240.0)]
+    );
+
+    let mut map = Beatmap::default();
+    assert_eq!(map.bpm_range(), (60.0, 60.0));
+    assert!((map.most_common_bpm() - 60.0).abs() < f64::EPSILON);
+    assert!(map.bpm_histogram().is_empty());
+    assert!(map.bpm_timeline().is_empty());
+}
+
+#[test]
+fn slider_colors() {
+    let content = "osu file format v14
+
+[Colours]
+Combo1 : 255,0,0
+SliderTrackOverride : 10,20,30
+SliderBorder : 224,224,224
+SomeCustomColor : 1,2,3
+";
+
+    let colors: Colors = rosu_map::from_str(content).unwrap();
+
+    assert_eq!(
+        colors.slider_track_override,
+        Some(Color::new(10, 20, 30, 255))
+    );
+    assert_eq!(colors.slider_border, Some(Color::new(224, 224, 224, 255)));
+    assert_eq!(colors.custom_colors.len(), 1);
+    assert_eq!(colors.custom_colors[0].name, "SomeCustomColor");
+
+    let colors: Colors = rosu_map::from_str(RENATUS).unwrap();
+    assert_eq!(colors.slider_track_override, None);
+    assert_eq!(colors.slider_border, None);
+}
+
+#[test]
+fn sv_multiplier_at() {
+    let map = Beatmap::from_path("./resources/overlapping-control-points.osu").unwrap();
+
+    assert!((map.sv_multiplier_at(500.0) - 1.5).abs() < 1e-6);
+    assert!((map.sv_multiplier_at(1500.0) - 1.5).abs() < 1e-6);
+    assert!((map.sv_multiplier_at(2500.0) - 0.75).abs() < 1e-6);
+    assert!((map.sv_multiplier_at(3500.0) - 1.5).abs() < 1e-6);
+
+    let default = DifficultyPoint::DEFAULT_SLIDER_VELOCITY;
+    assert!((Beatmap::default().sv_multiplier_at(0.0) - default).abs() < f64::EPSILON);
+}
+
+#[test]
+fn display_title_and_artist() {
+    let content = "osu file format v14
+
+[Metadata]
+Title:Title
+TitleUnicode:
+Artist:Artist
+ArtistUnicode:アーティスト
+";
+
+    let metadata = rosu_map::from_str::<Metadata>(content).unwrap();
+    assert_eq!(metadata.display_title(), "Title");
+    assert_eq!(metadata.display_artist(), "アーティスト");
+
+    let map = content.parse::<Beatmap>().unwrap();
+    assert_eq!(map.display_title(), "Title");
+    assert_eq!(map.display_artist(), "アーティスト");
+}
+
+#[test]
+fn control_point_insert_index() {
+    let content = "osu file format v14
+
+[TimingPoints]
+0,500,4,2,0,100,1,0
+1000,400,4,2,0,100,1,0
+2000,300,4,2,0,100,1,0
+";
+
+    let control_points = rosu_map::from_str::<TimingPoints>(content)
+        .unwrap()
+        .control_points;
+
+    assert_eq!(control_points.timing_insert_index(-500.0), 0);
+    assert_eq!(control_points.timing_insert_index(1500.0), 2);
+    assert_eq!(control_points.timing_insert_index(3000.0), 3);
+
+    // Exact-time collision
+    let idx = control_points.timing_insert_index(1000.0);
+    assert_eq!(idx, 1);
+    assert!((control_points.timing_points[idx].time - 1000.0).abs() < f64::EPSILON);
+
+    assert_eq!(control_points.difficulty_insert_index(1500.0), 0);
+}
+
+#[test]
+fn storyboard_files() {
+    let content = r#"osu file format v14
+
+[Events]
+0,0,"bg.jpg",0,0
+Sprite,Foreground,Centre,"sb\star.png",320,240
+ F,0,1000,2000,1,0
+Animation,Background,TopLeft,"sb/anim.png",0,0,4,100,LoopForever
+Sample,500,3,"hit.wav",70
+"#;
+
+    let events = rosu_map::from_str::<Events>(content).unwrap();
+
+    let expected = vec![
+        StoryboardFileRef {
+            kind: EventType::Sprite,
+            filename: "sb/star.png".to_owned(),
+            layer: "Foreground".to_owned(),
+            origin: Some("Centre".to_owned()),
+            time: None,
+            params: "320,240".to_owned(),
+        },
+        StoryboardFileRef {
+            kind: EventType::Animation,
+            filename: "sb/anim.png".to_owned(),
+            layer: "Background".to_owned(),
+            origin: Some("TopLeft".to_owned()),
+            time: None,
+            params: "0,0,4,100,LoopForever".to_owned(),
+        },
+        StoryboardFileRef {
+            kind: EventType::Sample,
+            filename: "hit.wav".to_owned(),
+            layer: "3".to_owned(),
+            origin: None,
+            time: Some(500.0),
+            params: "70".to_owned(),
+        },
+    ];
+
+    assert_eq!(events.background_file, "bg.jpg");
+    assert_eq!(events.storyboard_files, expected);
+}
+
+#[test]
+fn max_combo() {
+    let mut map = RENATUS.parse::<Beatmap>().unwrap();
+    assert_eq!(map.max_combo(), 1328);
+
+    let mut map = rosu_map::from_path::<Beatmap>("./resources/sample-beatmap-taiko.osu").unwrap();
+    assert_eq!(map.max_combo(), 18);
+
+    let mut map = rosu_map::from_path::<Beatmap>("./resources/sample-beatmap-catch.osu").unwrap();
+    assert_eq!(map.max_combo(), 19);
+
+    // 9 notes and 10 hold notes
+    let mut map = rosu_map::from_path::<Beatmap>("./resources/sample-beatmap-mania.osu").unwrap();
+    assert_eq!(map.max_combo(), 29);
+}
+
+#[test]
+fn strip_hit_sounds() {
+    let normal = [HitSampleInfo::new(HitSampleInfo::HIT_NORMAL, None, 0, 0)];
+
+    for path in [
+        "./resources/slider-samples.osu",
+        "./resources/hitobject-file-samples.osu",
+    ] {
+        let mut map = rosu_map::from_path::<Beatmap>(path).unwrap();
+        map.strip_hit_sounds();
+
+        for h in map.hit_objects {
+            assert_eq!(h.samples, normal);
+
+            if let HitObjectKind::Slider(slider) = h.kind {
+                assert!(slider
+                    .node_samples
+                    .iter()
+                    .all(|node_samples| node_samples == &normal));
+            }
+        }
+    }
+}
+
+#[test]
+fn end_time_of() {
+    let mut map = RENATUS.parse::<Beatmap>().unwrap();
+    let mut bufs = CurveBuffers::default();
+
+    let indices = [0, 1, 100, map.hit_objects.len() - 1];
+
+    let expected: Vec<_> = indices
+        .iter()
+        .map(|&i| map.hit_objects[i].clone().end_time())
+        .collect();
+
+    for _ in 0..2 {
+        let actual: Vec<_> = indices
+            .iter()
+            .map(|&i| map.end_time_of(i, &mut bufs).unwrap())
+            .collect();
+
+        assert_eq!(actual, expected);
+    }
+
+    assert_eq!(map.end_time_of(map.hit_objects.len(), &mut bufs), None);
+}
+
+#[test]
+fn end_pos() {
+    let content = "osu file format v14
+
+[HitObjects]
+100,100,1000,1,0,0:0:0:0:
+256,192,2000,2,0,L|356:192,1,50
+256,192,3000,2,0,L|356:192,2,50
+256,192,4000,2,0,L|256:192,1,0
+256,192,5000,12,0,6000,0:0:0:0:
+";
+
+    let mut hit_objects = rosu_map::from_str::<HitObjects>(content)
+        .unwrap()
+        .hit_objects;
+    let mut bufs = CurveBuffers::default();
+
+    let end_positions: Vec<_> = hit_objects
+        .iter_mut()
+        .map(|h| h.end_pos(&mut bufs))
+        .collect();
+
+    assert_eq!(
+        end_positions,
+        [
+            Some(Pos::new(100.0, 100.0)),
+            // The expected distance trims the curve
+            Some(Pos::new(306.0, 192.0)),
+            // Two spans end at the head
+            Some(Pos::new(256.0, 192.0)),
+            // Zero-length slider
+            Some(Pos::new(256.0, 192.0)),
+            Some(Pos::new(256.0, 192.0)),
+        ]
+    );
+
+    let mut hold = HitObject::hold(64.0, 0.0, 500.0);
+    assert_eq!(hold.end_pos(&mut bufs), None);
+}
+
+#[test]
+fn colors_with_whitespace_and_comment() {
+    let content = "osu file format v14
+
+[Colours]
+Combo1 : 255, 128, 0 // primary
+SliderBorder :  10 ,20,   30
+";
+
+    let colors: Colors = rosu_map::from_str(content).unwrap();
+
+    assert_eq!(colors.custom_combo_colors, [Color::new(255, 128, 0, 255)]);
+    assert_eq!(colors.slider_border, Some(Color::new(10, 20, 30, 255)));
+}
+
+#[test]
+fn sample_banks_used() {
+    let map = Beatmap::from_path("./resources/controlpoint-custom-samplebank.osu").unwrap();
+
+    let expected = HashSet::from([SampleBank::Normal, SampleBank::Soft]);
+    assert_eq!(map.sample_banks_used(), expected);
+
+    assert!(Beatmap::default().sample_banks_used().is_empty());
+}
+
+#[test]
+fn apply_stacking() {
+    fn stack_heights(map: &Beatmap) -> Vec<i32> {
+        map.hit_objects
+            .iter()
+            .map(|h| match h.kind {
+                HitObjectKind::Circle(ref circle) => circle.stack_height,
+                HitObjectKind::Slider(ref slider) => slider.stack_height,
+                _ => 0,
+            })
+            .collect()
+    }
+
+    let content = "osu file format v14
+
+[General]
+StackLeniency: 0.7
+
+[Difficulty]
+ApproachRate:9
+SliderMultiplier:1
+
+[HitObjects]
+256,192,1000,1,0,0:0:0:0:
+256,192,1100,1,0,0:0:0:0:
+256,192,1200,1,0,0:0:0:0:
+100,100,3000,2,0,L|200:100,1,100
+200,100,4100,1,0,0:0:0:0:
+200,100,4200,1,0,0:0:0:0:
+";
+
+    let mut map = content.parse::<Beatmap>().unwrap();
+    assert_eq!(stack_heights(&map), [0; 6]);
+
+    map.apply_stacking();
+
+    // Circles stacking at the end of a slider are stacked away from it
+    assert_eq!(stack_heights(&map), [2, 1, 0, 0, -1, -2]);
+
+    // Objects outside of the stack window are not stacked
+    map.approach_rate = 10.0;
+    map.stack_leniency = 0.1;
+    map.apply_stacking();
+    assert_eq!(stack_heights(&map), [0; 6]);
+
+    let mut map = content.replace("v14", "v5").parse::<Beatmap>().unwrap();
+    map.apply_stacking();
+    assert_eq!(stack_heights(&map), [2, 1, 0, 0, -1, -2]);
+}
+
+#[test]
+fn set_combo_offset() {
+    let mut circle = HitObjectCircle {
+        pos: Pos::new(0.0, 0.0),
+        new_combo: true,
+        combo_offset: 0,
+        stack_height: 0,
+    };
+
+    assert_eq!(circle.set_combo_offset(7), Ok(()));
+    assert_eq!(circle.combo_offset, 7);
+
+    assert_eq!(circle.set_combo_offset(8), Err(InvalidComboOffsetError));
+    assert_eq!(circle.set_combo_offset(-1), Err(InvalidComboOffsetError));
+    assert_eq!(circle.combo_offset, 7);
+
+    let content = "osu file format v14
+
+[HitObjects]
+256,192,0,6,0,L|356:192,1,100
+";
+
+    let mut map = rosu_map::from_str::<HitObjects>(content).unwrap();
+
+    let HitObjectKind::Slider(ref mut slider) = map.hit_objects[0].kind else {
+        unreachable!()
+    };
+
+    assert_eq!(slider.set_combo_offset(7), Ok(()));
+    assert_eq!(slider.set_combo_offset(8), Err(InvalidComboOffsetError));
+    assert_eq!(slider.combo_offset, 7);
+}
+
+#[test]
+fn hit_objects_in_range() {
+    let content = "osu file format v14
+
+[Difficulty]
+SliderMultiplier:1
+
+[HitObjects]
+256,192,1000,1,0,0:0:0:0:
+256,192,2000,2,0,L|356:192,1,100
+256,192,3000,12,0,8000,0:0:0:0:
+256,192,9000,1,0,0:0:0:0:
+";
+
+    let mut map = content.parse::<Beatmap>().unwrap();
+    let mut bufs = CurveBuffers::default();
+
+    let mut start_times = |from, to| {
+        map.hit_objects_in_range(from, to, &mut bufs)
+            .map(|h| h.start_time)
+            .collect::<Vec<_>>()
+    };
+
+    // The slider lasts until 3000 and the spinner until 8000
+    assert_eq!(start_times(2500.0, 3500.0), [2000.0, 3000.0]);
+    assert_eq!(start_times(5000.0, 6000.0), [3000.0]);
+    assert_eq!(start_times(500.0, 1000.0), [1000.0]);
+    assert_eq!(start_times(9000.0, 9500.0), [9000.0]);
+    assert_eq!(start_times(8500.0, 8900.0), Vec::<f64>::new());
+    assert_eq!(start_times(0.0, 10_000.0), [1000.0, 2000.0, 3000.0, 9000.0]);
+}
+
+#[test]
+fn hit_sound_type_clap() {
+    let clap = [HitSampleInfo::new(HitSampleInfo::HIT_CLAP, None, 0, 100)];
+
+    let kind = HitSoundType::from(clap.as_slice());
+    assert_eq!(kind, HitSoundType::from(8));
+    assert!(kind.has_flag(HitSoundType::CLAP));
+
+    let normal_finish = HitSoundType::from(HitSoundType::NORMAL | HitSoundType::FINISH);
+    assert!(!normal_finish.has_flag(HitSoundType::CLAP));
+}
+
+#[test]
+fn slider_event_types() {
+    let control_points = vec![
+        PathControlPoint::new(Pos::new(0.0, 0.0)),
+        PathControlPoint::new(Pos::new(100.0, 0.0)),
+    ];
+
+    let path = SliderPath::new(GameMode::Osu, control_points, Some(100.0));
+    let mut h = HitObject::slider(Pos::new(0.0, 0.0), 1000.0, path, 2);
+
+    let HitObjectKind::Slider(ref mut slider) = h.kind else {
+        unreachable!()
+    };
+
+    let mut bufs = CurveBuffers::default();
+    let mut ticks = Vec::new();
+
+    let events: Vec<SliderEvent> = slider
+        .events(h.start_time, 25.0, &mut bufs, &mut ticks)
+        .collect();
+
+    let count = |f: fn(SliderEventType) -> bool| events.iter().filter(|e| f(e.kind)).count();
+
+    assert_eq!(count(SliderEventType::is_head), 1);
+    assert_eq!(count(SliderEventType::is_tick), 9);
+    assert_eq!(count(SliderEventType::is_repeat), 2);
+    assert_eq!(count(SliderEventType::is_last_tick), 1);
+    assert_eq!(count(SliderEventType::is_tail), 1);
+
+    let repeats: Vec<_> = events
+        .iter()
+        .filter(|e| e.kind.is_repeat())
+        .map(|e| (e.span_idx, e.time, e.path_progress))
+        .collect();
+
+    assert_eq!(repeats, [(0, 1100.0, 1.0), (1, 1200.0, 0.0)]);
+}
+
+#[test]
+fn peak_objects_per_second() {
+    let content = "osu file format v14
+
+[HitObjects]
+256,192,0,1,0,0:0:0:0:
+256,192,1000,1,0,0:0:0:0:
+256,192,2000,1,0,0:0:0:0:
+256,192,2125,1,0,0:0:0:0:
+256,192,2250,1,0,0:0:0:0:
+256,192,2375,1,0,0:0:0:0:
+256,192,2500,1,0,0:0:0:0:
+256,192,2625,1,0,0:0:0:0:
+256,192,2750,1,0,0:0:0:0:
+256,192,2875,1,0,0:0:0:0:
+256,192,3000,1,0,0:0:0:0:
+";
+
+    let map = content.parse::<Beatmap>().unwrap();
+
+    // The stream from 2000 to 2875, the object at 3000 is just outside
+    assert!((map.peak_objects_per_second() - 8.0).abs() < f64::EPSILON);
+    assert!(Beatmap::default().peak_objects_per_second().abs() < f64::EPSILON);
+}
+
+#[test]
+fn game_mode_try_from_str() {
+    assert_eq!(GameMode::try_from_str("2"), Ok(GameMode::Catch));
+    assert_eq!(GameMode::try_from_str("osu"), Ok(GameMode::Osu));
+    assert_eq!(GameMode::try_from_str("Taiko"), Ok(GameMode::Taiko));
+    assert_eq!(GameMode::try_from_str("fruits"), Ok(GameMode::Catch));
+    assert_eq!(GameMode::try_from_str("CATCH"), Ok(GameMode::Catch));
+    assert_eq!(GameMode::try_from_str("mania"), Ok(GameMode::Mania));
+    assert_eq!(GameMode::try_from_str("4"), Err(ParseGameModeError));
+    assert_eq!(GameMode::try_from_str("ctb"), Err(ParseGameModeError));
+
+    // Decoding stays strict
+    assert_eq!("mania".parse::<GameMode>(), Err(ParseGameModeError));
+}
+
+#[test]
+fn timing_point_trailing_comma() {
+    let content = "osu file format v14
+
+[TimingPoints]
+956,329.67,
+";
+
+    let control_points = rosu_map::from_str::<TimingPoints>(content)
+        .unwrap()
+        .control_points;
+
+    assert_eq!(
+        control_points.timing_points,
+        [TimingPoint::new(
+            956.0,
+            329.67,
+            false,
+            TimeSignature::new_simple_quadruple()
+        )]
+    );
+
+    let sample_point = &control_points.sample_points[0];
+    assert_eq!(sample_point.sample_bank, SampleBank::Normal);
+    assert_eq!(
+        sample_point.sample_volume,
+        SamplePoint::DEFAULT_SAMPLE_VOLUME
+    );
+}
+
+#[test]
+fn control_points_iter_all() {
+    let control_points = rosu_map::from_str::<TimingPoints>(RENATUS)
+        .unwrap()
+        .control_points;
+
+    let all: Vec<_> = control_points.iter_all().collect();
+
+    let count = |f: fn(&ControlPointRef<'_>) -> bool| all.iter().filter(|point| f(point)).count();
+
+    assert_eq!(
+        count(|point| matches!(point, ControlPointRef::Timing(_))),
+        4
+    );
+    assert_eq!(
+        count(|point| matches!(point, ControlPointRef::Difficulty(_))),
+        5
+    );
+    assert_eq!(
+        count(|point| matches!(point, ControlPointRef::Sample(_))),
+        34
+    );
+    assert_eq!(
+        count(|point| matches!(point, ControlPointRef::Effect(_))),
+        8
+    );
+
+    assert!(all.windows(2).all(|w| w[0].time() <= w[1].time()));
+
+    // Timing points come first on ties
+    let first_timing = control_points.timing_points[0].time;
+    let first_at_time = all
+        .iter()
+        .find(|point| point.time() == first_timing)
+        .unwrap();
+    assert!(matches!(first_at_time, ControlPointRef::Timing(_)));
+}
+
+#[test]
+fn metadata_view() {
+    let map = RENATUS.parse::<Beatmap>().unwrap();
+    let metadata: Metadata = rosu_map::from_str(RENATUS).unwrap();
+
+    let view = map.metadata_view();
+
+    assert_eq!(view.title, metadata.title);
+    assert_eq!(view.artist_unicode, metadata.artist_unicode);
+    assert_eq!(view.version, metadata.version);
+    assert_eq!(*view.beatmap_id, metadata.beatmap_id);
+    assert_eq!(*map.difficulty_view().circle_size, map.circle_size);
+}
+
+#[test]
+fn offset_time() {
+    let original = RENATUS.parse::<Beatmap>().unwrap();
+    let mut map = original.clone();
+
+    map.offset_time(1000.0);
+
+    assert_eq!(
+        map.hit_objects[0].start_time,
+        original.hit_objects[0].start_time + 1000.0
+    );
+    assert_eq!(
+        map.control_points.timing_points[0].time,
+        original.control_points.timing_points[0].time + 1000.0
+    );
+    assert_eq!(map.breaks[0].end_time, original.breaks[0].end_time + 1000.0);
+    assert_eq!(map.preview_time, original.preview_time + 1000);
+    assert_eq!(map.bookmarks[0], original.bookmarks[0] + 1000);
+
+    map.offset_time(-1000.0);
+
+    assert!(map.approx_eq(&original, 1e-9));
+    assert_eq!(map.preview_time, original.preview_time);
+    assert_eq!(map.bookmarks, original.bookmarks);
+
+    let mut map = Beatmap::default();
+    map.offset_time(1000.0);
+    assert_eq!(map.preview_time, -1);
+}
+
+#[test]
+fn control_points_debug_timeline() {
+    let control_points = rosu_map::from_str::<TimingPoints>(RENATUS)
+        .unwrap()
+        .control_points;
+
+    let timeline = control_points.debug_timeline();
+    let mut lines = timeline.lines();
+
+    assert_eq!(lines.next(), Some("956ms timing: 182.00 BPM, 4/4"));
+    assert_eq!(
+        lines.next(),
+        Some("956ms sample: soft bank, 60% volume, custom bank 0")
+    );
+    assert!(timeline.contains("53703ms effect: kiai on, 1.00x scroll speed\n"));
+    assert_eq!(timeline.lines().count(), 4 + 5 + 34 + 8);
+}
+
+#[test]
+fn variables() {
+    let map = Beatmap::from_path("./resources/variables.osu").unwrap();
+
+    assert_eq!(map.background_file, "backgrounds/BG.jpg");
+    assert_eq!(map.storyboard_files.len(), 2);
+    assert_eq!(map.storyboard_files[0].filename, "hit.wav");
+
+    // Self-referencing variables must not loop forever
+    assert!(map.storyboard_files[1].filename.starts_with("$self$"));
+}
+
+#[test]
+fn self_doubling_variables() {
+    let mut content = "osu file format v14\n\n[Variables]\n".to_owned();
+
+    for i in 0..30 {
+        content.push_str(&format!("$v{i}=$a\n"));
+    }
+
+    content.push_str("$a=$a$a\n\n[Events]\nSample,1000,0,\"$a.wav\",100\n");
+
+    let map = content.parse::<Beatmap>().unwrap();
+
+    // The expansion is too long so the line is kept as is
+    assert_eq!(map.storyboard_files.len(), 1);
+    assert_eq!(map.storyboard_files[0].filename, "$a.wav");
+}
+
+#[test]
+fn dedup_control_points_by_time() {
+    let mut map = Beatmap::default();
+
+    map.control_points.timing_points = vec![
+        TimingPoint::new(0.0, 500.0, false, TimeSignature::new_simple_quadruple()),
+        TimingPoint::new(1000.0, 400.0, false, TimeSignature::new_simple_quadruple()),
+        TimingPoint::new(1000.0, 300.0, false, TimeSignature::new_simple_quadruple()),
+        TimingPoint::new(2000.0, 200.0, false, TimeSignature::new_simple_quadruple()),
+    ];
+
+    map.merge_duplicate_timing_points();
+
+    let beat_lens: Vec<_> = map
+        .control_points
+        .timing_points
+        .iter()
+        .map(|point| (point.time, point.beat_len))
+        .collect();
+
+    assert_eq!(beat_lens, [(0.0, 500.0), (1000.0, 300.0), (2000.0, 200.0)]);
+}
+
+#[test]
+fn hit_sound_type_bits() {
+    let kind = HitSoundType::from_bits(HitSoundType::WHISTLE | HitSoundType::FINISH);
+
+    assert_eq!(kind.bits(), 6);
+    assert!(kind.contains(HitSoundType::WHISTLE));
+    assert!(kind.contains(HitSoundType::FINISH));
+    assert!(!kind.contains(HitSoundType::CLAP));
+    assert!(!kind.contains(HitSoundType::NONE));
+    assert_eq!(HitSoundType::from_bits(kind.bits()), kind);
+}
+
+#[test]
+fn split_at_time() {
+    let map = RENATUS.parse::<Beatmap>().unwrap();
+    let hit_objects_len = map.hit_objects.len();
+    let sample_point = map.sample_point_at(60_000.0).cloned().unwrap();
+
+    let preview_time = map.preview_time;
+    let bookmarks_len = map.bookmarks.len();
+
+    let (before, after) = map.split_at_time(60_000.0);
+
+    // Renatus' preview time is after the split
+    assert_eq!(before.preview_time, -1);
+    assert_eq!(after.preview_time, preview_time - 60_000);
+    assert_eq!(
+        before.bookmarks.len() + after.bookmarks.len(),
+        bookmarks_len
+    );
+    assert!(before.bookmarks.iter().all(|&bookmark| bookmark <= 60_000));
+    assert!(after.bookmarks.iter().all(|&bookmark| bookmark > 0));
+
+    assert_eq!(before.hit_objects.len(), 295);
+    assert_eq!(
+        before.hit_objects.len() + after.hit_objects.len(),
+        hit_objects_len
+    );
+    assert!(after.hit_objects.iter().all(|h| h.start_time > 0.0));
+
+    let carried_sample_point = &after.control_points.sample_points[0];
+    assert_eq!(carried_sample_point.time, 0.0);
+    assert_eq!(carried_sample_point.sample_bank, sample_point.sample_bank);
+    assert_eq!(
+        carried_sample_point.sample_volume,
+        sample_point.sample_volume
+    );
+
+    assert_eq!(after.control_points.timing_points[0].time, 0.0);
+    assert_eq!(
+        after.control_points.timing_points[0].beat_len,
+        before.control_points.timing_points[0].beat_len
+    );
+}
+
+#[test]
+fn beatmap_summary() {
+    let summary =
+        rosu_map::from_path::<BeatmapSummary>("./resources/Soleily - Renatus (Gamu) [Insane].osu")
+            .unwrap();
+
+    let expected = BeatmapSummary {
+        audio_file: "03. Renatus - Soleily 192kbps.mp3".to_owned(),
+        mode: GameMode::Osu,
+        title: "Renatus".to_owned(),
+        artist: "Soleily".to_owned(),
+        creator: "Gamu".to_owned(),
+        version: "Insane".to_owned(),
+        beatmap_id: 557_821,
+        beatmap_set_id: 241_526,
+    };
+
+    assert_eq!(summary, expected);
+
+    let map: Beatmap = RENATUS.parse().unwrap();
+    assert_eq!(map.title, summary.title);
+    assert_eq!(map.audio_file, summary.audio_file);
+}
+
+#[test]
+fn indented_metadata() {
+    let content = "osu file format v14
+
+[Metadata]
+  Title:Renatus
+\tTags:  MBC7 Unisphere
+";
+
+    let metadata = rosu_map::from_str::<Metadata>(content).unwrap();
+
+    assert_eq!(metadata.title, "Renatus");
+    assert_eq!(metadata.tags, "MBC7 Unisphere");
+}
+
+#[test]
+fn apply_rate() {
+    let mut map = RENATUS.parse::<Beatmap>().unwrap();
+    let mut bufs = CurveBuffers::default();
+
+    let start_time = map.hit_objects.last().unwrap().start_time;
+    let end_times: Vec<_> = map
+        .hit_objects
+        .iter_mut()
+        .map(|h| h.end_time_with_bufs(&mut bufs))
+        .collect();
+    let beat_len = map.control_points.timing_points[0].beat_len;
+
+    let preview_time = map.preview_time;
+
+    map.apply_rate(1.5);
+
+    assert_eq!(
+        map.preview_time,
+        (f64::from(preview_time) / 1.5).round() as i32
+    );
+
+    assert!((map.hit_objects.last().unwrap().start_time - start_time / 1.5).abs() < 1e-9);
+    assert!((map.control_points.timing_points[0].beat_len - beat_len / 1.5).abs() < 1e-9);
+
+    for (h, end_time) in map.hit_objects.iter_mut().zip(end_times) {
+        assert!((h.end_time_with_bufs(&mut bufs) - end_time / 1.5).abs() < 1e-6);
+    }
+}
+
+#[test]
+fn spatial_distance() {
+    let a = HitObject::circle(Pos::new(100.0, 100.0), 0.0);
+    let b = HitObject::circle(Pos::new(103.0, 104.0), 100.0);
+
+    assert!((a.spatial_distance(&b) - 5.0).abs() < f32::EPSILON);
+    assert!(a.spatial_distance(&a).abs() < f32::EPSILON);
+
+    let hold = HitObject::hold(256.0, 0.0, 100.0);
+    let spinner = HitObject::spinner(0.0, 1000.0);
+    assert!(hold.spatial_distance(&spinner).abs() < f32::EPSILON);
+}
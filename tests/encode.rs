@@ -2,13 +2,16 @@ use std::{fmt::Debug, fs, num::NonZeroI32};
 
 use rosu_map::{
     section::{
+        colors::Color,
+        events::BreakPeriod,
         general::GameMode,
         hit_objects::{
             HitObject, HitObjectKind, HitObjectSlider, PathControlPoint, PathType, SliderPath,
         },
+        timing_points::{DifficultyPoint, EffectPoint, TimeSignature, TimingPoint},
     },
     util::Pos,
-    Beatmap,
+    Beatmap, EncodeOptions, EventTypeStyle, LATEST_FORMAT_VERSION,
 };
 use test_log::test;
 
@@ -48,6 +51,11 @@ fn stability() {
             &decoded_after_encode.control_points.effect_points,
             filename,
         );
+        assert_eq_list(
+            &decoded.control_points.sample_points,
+            &decoded_after_encode.control_points.sample_points,
+            filename,
+        );
         assert_eq_list(
             &decoded.hit_objects,
             &decoded_after_encode.hit_objects,
@@ -75,6 +83,49 @@ fn stability() {
     }
 }
 
+#[test]
+fn reconstruct_sample_points() {
+    let mut decoded = Beatmap::from_path("./resources/hitobject-file-samples.osu").unwrap();
+    decoded.control_points.sample_points.clear();
+
+    let mut bytes = Vec::with_capacity(2048);
+    decoded.encode(&mut bytes).unwrap();
+    let decoded_after_encode = Beatmap::from_bytes(&bytes).unwrap();
+
+    assert_eq!(decoded.hit_objects, decoded_after_encode.hit_objects);
+    assert_eq!(
+        decoded_after_encode
+            .control_points
+            .sample_points
+            .iter()
+            .map(|point| (point.time, point.sample_volume, point.custom_sample_bank))
+            .collect::<Vec<_>>(),
+        [(2170.0, 40, 0), (3107.0, 40, 2), (3576.0, 70, 0)]
+    );
+}
+
+#[test]
+fn omit_sample_values_provided_by_sample_points() {
+    let mut map = Beatmap::from_path("./resources/hitobject-file-samples.osu").unwrap();
+    let encoded = map.encode_to_string().unwrap();
+
+    let hit_objects: Vec<_> = encoded
+        .lines()
+        .skip_while(|line| *line != "[HitObjects]")
+        .skip(1)
+        .collect();
+
+    assert_eq!(
+        hit_objects,
+        [
+            "255,193,2170,5,0,0:0:0:0:hit_1.wav",
+            "256,191,2638,5,0,0:0:0:0:hit_2.wav",
+            "255,193,3107,1,0,1:0:0:0:",
+            "256,191,3576,1,0,0:0:0:70:hit_1.wav",
+        ]
+    );
+}
+
 #[test]
 fn bspline_curve_type() {
     let control_points = vec![
@@ -106,6 +157,7 @@ fn bspline_curve_type() {
         node_samples: Vec::new(),
         repeat_count: 0,
         velocity: 0.0,
+        stack_height: 0,
     };
 
     let hit_object = HitObject {
@@ -134,6 +186,16 @@ fn bspline_curve_type() {
 
     assert_eq!(actual.path.control_points().len(), 4);
     assert_eq!(expected.path.control_points(), actual.path.control_points());
+
+    let degrees: Vec<_> = actual
+        .path
+        .control_points()
+        .iter()
+        .map(|point| point.path_type.and_then(|path_type| path_type.degree()))
+        .collect();
+
+    assert_eq!(degrees, [Some(3), None, Some(3), None]);
+    assert_eq!(PathType::BEZIER.degree(), None);
 }
 
 #[test]
@@ -171,6 +233,7 @@ fn multi_segment_slider_with_floating_point_error() {
         node_samples: Vec::new(),
         repeat_count: 0,
         velocity: 0.0,
+        stack_height: 0,
     };
 
     let hit_object = HitObject {
@@ -195,3 +258,546 @@ fn multi_segment_slider_with_floating_point_error() {
 
     assert_eq!(decoded_slider.path.control_points().len(), 5);
 }
+
+#[test]
+fn header_only() {
+    let map = Beatmap::from_path("./resources/hitobject-file-samples.osu").unwrap();
+    let header = map.encode_header_only();
+
+    assert!(header.starts_with("osu file format v"));
+    assert!(header.contains("[General]"));
+    assert!(header.contains("[Metadata]"));
+    assert!(header.contains("[TimingPoints]"));
+    assert!(!header.contains("[HitObjects]"));
+    assert!(!header.contains("[Events]"));
+
+    let decoded = Beatmap::from_bytes(header.as_bytes()).unwrap();
+    assert_eq!(decoded.control_points, map.control_points);
+    assert!(decoded.hit_objects.is_empty());
+}
+
+/// Encodes and decodes the map twice and asserts that all fields stay the
+/// same across both cycles.
+#[track_caller]
+fn assert_roundtrip(map: &mut Beatmap, filename: &str) {
+    macro_rules! assert_fields {
+        ( $expected:ident, $actual:ident, $cycle:ident: $( $field:ident ),* $(,)? ) => {
+            // Destructuring ensures that newly added fields must be covered
+            let Beatmap { $( $field: _ ),* } = $expected;
+
+            $(
+                assert_eq!(
+                    $expected.$field,
+                    $actual.$field,
+                    "{filename:?} cycle {}: field `{}`",
+                    $cycle,
+                    stringify!($field),
+                );
+            )*
+        };
+    }
+
+    let mut bytes = Vec::with_capacity(4096);
+
+    let mut decoded = map.clone();
+
+    for cycle in 1..=2 {
+        bytes.clear();
+
+        decoded
+            .encode(&mut bytes)
+            .unwrap_or_else(|e| panic!("Failed to encode beatmap {filename:?}: {e:?}"));
+
+        let decoded_after_encode = Beatmap::from_bytes(&bytes).unwrap_or_else(|e| {
+            panic!("Failed to decode beatmap after encoding {filename:?}: {e:?}")
+        });
+
+        let expected = &*map;
+        let actual = &decoded_after_encode;
+
+        assert_fields!(expected, actual, cycle:
+            format_version,
+            audio_file,
+            audio_lead_in,
+            preview_time,
+            default_sample_bank,
+            default_sample_volume,
+            stack_leniency,
+            mode,
+            letterbox_in_breaks,
+            special_style,
+            widescreen_storyboard,
+            epilepsy_warning,
+            samples_match_playback_rate,
+            countdown,
+            countdown_offset,
+            bookmarks,
+            distance_spacing,
+            beat_divisor,
+            grid_size,
+            timeline_zoom,
+            title,
+            title_unicode,
+            artist,
+            artist_unicode,
+            creator,
+            version,
+            source,
+            tags,
+            beatmap_id,
+            beatmap_set_id,
+            hp_drain_rate,
+            circle_size,
+            overall_difficulty,
+            approach_rate,
+            slider_multiplier,
+            slider_tick_rate,
+            background_file,
+            breaks,
+            background_colors,
+            storyboard_files,
+            control_points,
+            custom_combo_colors,
+            custom_colors,
+            slider_track_override,
+            slider_border,
+            hit_objects,
+        );
+
+        decoded = decoded_after_encode;
+    }
+}
+
+#[test]
+fn roundtrip() {
+    for entry in fs::read_dir("./resources").unwrap() {
+        let entry = entry.unwrap();
+        let filename = entry.file_name();
+        let filename = filename.to_str().unwrap();
+
+        if !filename.ends_with(".osu") {
+            continue;
+        }
+
+        let mut map = Beatmap::from_path(entry.path())
+            .unwrap_or_else(|e| panic!("Failed to decode beatmap {filename:?}: {e:?}"));
+
+        assert_roundtrip(&mut map, filename);
+    }
+}
+
+#[test]
+fn layered_samples() {
+    let content = "osu file format v14
+
+[TimingPoints]
+0,500,4,2,0,100,1,0
+
+[HitObjects]
+256,192,1000,1,0,0:0:0:0:
+256,192,1500,1,2,0:0:0:0:
+256,192,2000,1,3,2:3:0:0:
+256,192,2500,1,8,0:0:0:0:hit.wav
+";
+
+    let mut map = content.parse::<Beatmap>().unwrap();
+
+    let is_layered: Vec<_> = map
+        .hit_objects
+        .iter()
+        .map(|h| h.samples[0].is_layered)
+        .collect();
+
+    assert_eq!(is_layered, [false, true, false, false]);
+
+    assert_roundtrip(&mut map, "layered samples");
+}
+
+#[test]
+fn default_format_version() {
+    let mut map = Beatmap::default();
+    assert_eq!(map.format_version, LATEST_FORMAT_VERSION);
+
+    let encoded = map.encode_to_string().unwrap();
+    let expected = format!("osu file format v{LATEST_FORMAT_VERSION}\n");
+    assert!(encoded.starts_with(&expected));
+}
+
+#[test]
+fn hit_object_constructors() {
+    let path = SliderPath::new(
+        GameMode::Osu,
+        vec![
+            PathControlPoint::new(Pos::new(0.0, 0.0)),
+            PathControlPoint::new(Pos::new(100.0, 0.0)),
+        ],
+        None,
+    );
+
+    let mut map = Beatmap {
+        hit_objects: vec![
+            HitObject::circle(Pos::new(100.0, 100.0), 1000.0),
+            HitObject::slider(Pos::new(200.0, 100.0), 2000.0, path, 1),
+            HitObject::spinner(3000.0, 1000.0),
+        ],
+        ..Default::default()
+    };
+
+    let decoded = map.encode_to_string().unwrap().parse::<Beatmap>().unwrap();
+
+    assert_eq!(decoded.hit_objects.len(), 3);
+
+    let HitObjectKind::Circle(ref circle) = decoded.hit_objects[0].kind else {
+        panic!("expected circle");
+    };
+    assert_eq!(circle.pos, Pos::new(100.0, 100.0));
+
+    let HitObjectKind::Slider(ref slider) = decoded.hit_objects[1].kind else {
+        panic!("expected slider");
+    };
+    assert_eq!(slider.pos, Pos::new(200.0, 100.0));
+    assert_eq!(slider.repeat_count, 1);
+    assert_eq!(slider.node_samples.len(), 3);
+
+    let HitObjectKind::Spinner(ref spinner) = decoded.hit_objects[2].kind else {
+        panic!("expected spinner");
+    };
+    assert!((spinner.duration - 1000.0).abs() < f64::EPSILON);
+
+    let mut map = Beatmap {
+        mode: GameMode::Mania,
+        circle_size: 4.0,
+        hit_objects: vec![HitObject::hold(64.0, 1000.0, 500.0)],
+        ..Default::default()
+    };
+
+    let decoded = map.encode_to_string().unwrap().parse::<Beatmap>().unwrap();
+
+    let HitObjectKind::Hold(ref hold) = decoded.hit_objects[0].kind else {
+        panic!("expected hold");
+    };
+    assert!((hold.pos_x - 64.0).abs() < f32::EPSILON);
+    assert!((hold.duration - 500.0).abs() < f64::EPSILON);
+}
+
+#[test]
+fn windows_paths() {
+    let content = "osu file format v14
+
+[General]
+AudioFilename: folder\\song.mp3
+
+[Events]
+0,0,\"folder\\bg.jpg\",0,0
+";
+
+    let mut map = content.parse::<Beatmap>().unwrap();
+    assert_eq!(map.audio_file, "folder/song.mp3");
+    assert_eq!(map.background_file, "folder/bg.jpg");
+
+    let encoded = map.encode_to_string().unwrap();
+    assert!(encoded.contains("AudioFilename: folder\\song.mp3\n"));
+    assert!(encoded.contains("0,0,\"folder\\bg.jpg\",0,0\n"));
+
+    let decoded = encoded.parse::<Beatmap>().unwrap();
+    assert_eq!(decoded.audio_file, map.audio_file);
+    assert_eq!(decoded.background_file, map.background_file);
+}
+
+#[test]
+fn deterministic() {
+    for entry in fs::read_dir("./resources").unwrap() {
+        let entry = entry.unwrap();
+        let filename = entry.file_name();
+        let filename = filename.to_str().unwrap();
+
+        if !filename.ends_with(".osu") {
+            continue;
+        }
+
+        let mut map = Beatmap::from_path(entry.path()).unwrap();
+
+        let mut first = Vec::new();
+        map.encode(&mut first).unwrap();
+
+        let mut second = Vec::new();
+        map.encode(&mut second).unwrap();
+
+        assert_eq!(first, second, "{filename:?}");
+    }
+}
+
+#[test]
+fn mutate_control_points() {
+    let content = "osu file format v14
+
+[TimingPoints]
+0,500,4,2,0,100,1,0
+2000,-50,4,2,0,100,0,1
+";
+
+    let mut map = content.parse::<Beatmap>().unwrap();
+    let original = map.control_points.clone();
+
+    // Redundant points are discarded
+    map.control_points
+        .add_effect_point(EffectPoint::new(3000.0, true));
+    map.control_points
+        .add_difficulty_point(DifficultyPoint::new(3000.0, 500.0, 2.0));
+    assert_eq!(map.control_points, original);
+
+    let timing_point =
+        TimingPoint::new(1000.0, 250.0, false, TimeSignature::new_simple_quadruple());
+    map.control_points.add_timing_point(timing_point.clone());
+
+    let times: Vec<_> = map
+        .control_points
+        .timing_points
+        .iter()
+        .map(|point| point.time)
+        .collect();
+
+    assert_eq!(times, [0.0, 1000.0]);
+    assert_roundtrip(&mut map, "added timing point");
+
+    assert_eq!(
+        map.control_points.remove_timing_point_at(1000.0),
+        Some(timing_point)
+    );
+    assert_eq!(map.control_points.remove_timing_point_at(1000.0), None);
+    assert_eq!(map.control_points, original);
+}
+
+#[test]
+fn compute_md5() {
+    let mut map = Beatmap::from_path("./resources/Soleily - Renatus (Gamu) [Insane].osu").unwrap();
+
+    let encoded = map.encode_to_string().unwrap();
+    assert_eq!(
+        map.compute_md5(),
+        rosu_map::md5_of_bytes(encoded.as_bytes())
+    );
+    assert_eq!(map.compute_md5().len(), 32);
+}
+
+#[test]
+fn equal_time_order() {
+    let content = "osu file format v14
+
+[HitObjects]
+100,100,500,1,0,0:0:0:0:
+300,100,1000,1,0,0:0:0:0:
+200,100,1000,1,0,0:0:0:0:
+400,100,1000,1,0,0:0:0:0:
+";
+
+    let mut map = content.parse::<Beatmap>().unwrap();
+
+    let xs: Vec<_> = map
+        .hit_objects
+        .iter()
+        .map(|h| match h.kind {
+            HitObjectKind::Circle(ref circle) => circle.pos.x,
+            _ => panic!("Expected a circle"),
+        })
+        .collect();
+    assert_eq!(xs, [100.0, 300.0, 200.0, 400.0]);
+
+    assert_roundtrip(&mut map, "equal time order");
+}
+
+#[test]
+fn slider_colors() {
+    let mut map = Beatmap {
+        slider_track_override: Some(Color::new(10, 20, 30, 255)),
+        slider_border: Some(Color::new(224, 224, 224, 255)),
+        ..Default::default()
+    };
+
+    let encoded = map.encode_to_string().unwrap();
+    assert!(encoded.contains("SliderTrackOverride: 10,20,30,255\n"));
+    assert!(encoded.contains("SliderBorder: 224,224,224,255\n"));
+
+    assert_roundtrip(&mut map, "slider colors");
+}
+
+#[test]
+fn event_type_style() {
+    let mut map = Beatmap {
+        background_file: "bg.jpg".to_owned(),
+        breaks: vec![BreakPeriod {
+            start_time: 1000.0,
+            end_time: 3000.0,
+        }],
+        ..Default::default()
+    };
+
+    let encoded = map.encode_to_string().unwrap();
+    assert!(encoded.contains("0,0,\"bg.jpg\",0,0\n"));
+    assert!(encoded.contains("2,1000,3000\n"));
+
+    let options = EncodeOptions {
+        event_type_style: EventTypeStyle::Name,
+        ..Default::default()
+    };
+
+    let mut bytes = Vec::new();
+    map.encode_with_options(&mut bytes, &options).unwrap();
+    let encoded = String::from_utf8(bytes).unwrap();
+    assert!(encoded.contains("Background,0,\"bg.jpg\",0,0\n"));
+    assert!(encoded.contains("Break,1000,3000\n"));
+
+    let decoded = encoded.parse::<Beatmap>().unwrap();
+    assert_eq!(decoded.background_file, map.background_file);
+    assert_eq!(decoded.breaks, map.breaks);
+}
+
+#[test]
+fn storyboard_files() {
+    let content = r#"osu file format v14
+
+[Events]
+Sprite,Foreground,Centre,"sb\star.png",320,240
+Sample,500,3,"hit.wav",70
+"#;
+
+    let mut map = content.parse::<Beatmap>().unwrap();
+    assert_eq!(map.storyboard_files.len(), 2);
+
+    let encoded = map.encode_to_string().unwrap();
+    assert!(encoded.contains("4,Foreground,Centre,\"sb\\star.png\",320,240\n"));
+    assert!(encoded.contains("5,500,3,\"hit.wav\",70\n"));
+
+    assert_roundtrip(&mut map, "storyboard files");
+}
+
+#[test]
+fn events_order() {
+    let content = r#"osu file format v14
+
+[Events]
+Sample,500,3,"hit.wav",70
+2,5000,6000
+3,2000,10,20,30
+Sprite,Foreground,Centre,"star.png",320,240
+2,1000,3000
+0,0,"bg.jpg",0,0
+3,1000,40,50,60
+"#;
+
+    let expected = r#"[Events]
+0,0,"bg.jpg",0,0
+2,1000,3000
+2,5000,6000
+3,1000,40,50,60
+3,2000,10,20,30
+5,500,3,"hit.wav",70
+4,Foreground,Centre,"star.png",320,240
+"#;
+
+    let events_block = |encoded: &str| {
+        let start = encoded.find("[Events]").unwrap();
+        let end = encoded[start..].find("\n\n").unwrap() + start + 1;
+
+        encoded[start..end].to_owned()
+    };
+
+    let mut map = content.parse::<Beatmap>().unwrap();
+    let encoded = map.encode_to_string().unwrap();
+    assert_eq!(events_block(&encoded), expected);
+
+    let mut decoded = encoded.parse::<Beatmap>().unwrap();
+    let reencoded = decoded.encode_to_string().unwrap();
+    assert_eq!(events_block(&reencoded), expected);
+}
+
+#[test]
+fn strip_storyboard() {
+    let content = r#"osu file format v14
+
+[Events]
+0,0,"bg.jpg",0,0
+2,1000,3000
+3,1000,40,50,60
+Sprite,Foreground,Centre,"star.png",320,240
+Sample,500,3,"hit.wav",70
+"#;
+
+    let mut map = content.parse::<Beatmap>().unwrap();
+
+    map.strip_storyboard();
+    assert_eq!(map.background_file, "bg.jpg");
+    assert_eq!(map.breaks.len(), 1);
+
+    map.clear_breaks();
+    let encoded = map.encode_to_string().unwrap();
+    assert!(encoded.contains("[Events]\n0,0,\"bg.jpg\",0,0\n\n"));
+
+    assert_roundtrip(&mut map, "strip storyboard");
+}
+
+#[test]
+fn omit_default_approach_rate() {
+    let mut map = Beatmap::from_path("./resources/undefined-approach-rate.osu").unwrap();
+    assert_eq!(map.approach_rate, map.overall_difficulty);
+
+    let options = EncodeOptions {
+        omit_default_approach_rate: true,
+        ..Default::default()
+    };
+
+    let mut bytes = Vec::new();
+    map.encode_with_options(&mut bytes, &options).unwrap();
+    let encoded = String::from_utf8(bytes).unwrap();
+    assert!(!encoded.contains("ApproachRate"));
+    assert!(encoded.contains("OverallDifficulty: 1\nSliderMultiplier"));
+
+    let mut decoded = encoded.parse::<Beatmap>().unwrap();
+    assert_eq!(decoded.approach_rate, map.approach_rate);
+
+    let mut bytes = Vec::new();
+    decoded.encode_with_options(&mut bytes, &options).unwrap();
+    assert_eq!(String::from_utf8(bytes).unwrap(), encoded);
+
+    map.approach_rate = 9.0;
+    let mut bytes = Vec::new();
+    map.encode_with_options(&mut bytes, &options).unwrap();
+    assert!(String::from_utf8(bytes)
+        .unwrap()
+        .contains("ApproachRate: 9\n"));
+
+    assert!(map
+        .encode_to_string()
+        .unwrap()
+        .contains("ApproachRate: 9\n"));
+}
+
+#[test]
+fn reject_non_finite() {
+    let mut map = Beatmap::from_path("./resources/Soleily - Renatus (Gamu) [Insane].osu").unwrap();
+
+    let options = EncodeOptions {
+        reject_non_finite: true,
+        ..Default::default()
+    };
+
+    let mut bytes = Vec::new();
+    assert!(map.encode_with_options(&mut bytes, &options).is_ok());
+
+    map.approach_rate = f32::NAN;
+
+    bytes.clear();
+    let err = map.encode_with_options(&mut bytes, &options).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert!(err.to_string().contains("ApproachRate"));
+    assert!(bytes.is_empty());
+
+    map.approach_rate = 9.0;
+    map.control_points.difficulty_points[0].slider_velocity = f64::INFINITY;
+
+    let err = map.encode_with_options(&mut bytes, &options).unwrap_err();
+    assert!(err.to_string().contains("control points"));
+
+    // Without the option, values are written as they are
+    assert!(map.encode(&mut bytes).is_ok());
+}
@@ -1,4 +1,4 @@
-use rosu_map::Beatmap;
+use rosu_map::{Beatmap, EncodeOptions, Encoding};
 
 #[test]
 fn utf8_no_bom() {
@@ -24,22 +24,65 @@ fn utf8_bom() {
 
 #[test]
 fn utf16_le() {
-    let bytes = b"\xFF\xFEo\0s\0u\0 \0f\0i\0l\0e\0 \0f\0o\0r\0m\0a\0t\0 \0v\04\02\0\n\0\n\0";
+    let bytes = b"\xFF\xFEo\0s\0u\0 \0f\0i\0l\0e\0 \0f\0o\0r\0m\0a\0t\0 \0v\x004\x002\0\n\0\n\0";
     let map = Beatmap::from_bytes(bytes).unwrap();
     assert_eq!(map.format_version, 42);
 
-    let bytes = b"\xFF\xFE\x09\x20\n\0\n\0o\0s\0u\0 \0f\0i\0l\0e\0 \0f\0o\0r\0m\0a\0t\0 \0v\04\02\0\n\0\n\0";
+    let bytes = b"\xFF\xFE\x09\x20\n\0\n\0o\0s\0u\0 \0f\0i\0l\0e\0 \0f\0o\0r\0m\0a\0t\0 \0v\x004\x002\0\n\0\n\0";
     let map = Beatmap::from_bytes(bytes).unwrap();
     assert_eq!(map.format_version, 42);
 }
 
 #[test]
 fn utf16_be() {
-    let bytes = b"\xFE\xFF\0o\0s\0u\0 \0f\0i\0l\0e\0 \0f\0o\0r\0m\0a\0t\0 \0v\04\02\0\n\0\n";
+    let bytes = b"\xFE\xFF\0o\0s\0u\0 \0f\0i\0l\0e\0 \0f\0o\0r\0m\0a\0t\0 \0v\x004\x002\0\n\0\n";
     let map = Beatmap::from_bytes(bytes).unwrap();
     assert_eq!(map.format_version, 42);
 
-    let bytes = b"\xFE\xFF\x20\x09\0\n\0\n\0o\0s\0u\0 \0f\0i\0l\0e\0 \0f\0o\0r\0m\0a\0t\0 \0v\04\02\0\n\0\n";
+    let bytes = b"\xFE\xFF\x20\x09\0\n\0\n\0o\0s\0u\0 \0f\0i\0l\0e\0 \0f\0o\0r\0m\0a\0t\0 \0v\x004\x002\0\n\0\n";
     let map = Beatmap::from_bytes(bytes).unwrap();
     assert_eq!(map.format_version, 42);
 }
+
+#[test]
+fn encode_with_encoding() {
+    let mut map = Beatmap::from_path("./resources/Soleily - Renatus (Gamu) [Insane].osu").unwrap();
+
+    let mut utf8 = Vec::new();
+    map.encode(&mut utf8).unwrap();
+
+    let mut utf8_bom = Vec::new();
+    let options = EncodeOptions {
+        write_bom: true,
+        ..Default::default()
+    };
+    map.encode_with_options(&mut utf8_bom, &options).unwrap();
+
+    assert_eq!(utf8_bom[..3], *b"\xEF\xBB\xBF");
+    assert_eq!(utf8_bom[3..], utf8);
+
+    for (encoding, bom) in [
+        (Encoding::Utf16LE, b"\xFF\xFE"),
+        (Encoding::Utf16BE, b"\xFE\xFF"),
+    ] {
+        let options = EncodeOptions {
+            encoding,
+            write_bom: true,
+            ..Default::default()
+        };
+
+        let mut utf16 = Vec::new();
+        map.encode_with_options(&mut utf16, &options).unwrap();
+        assert_eq!(utf16[..2], *bom);
+
+        let mut decoded = Beatmap::from_bytes(&utf16).unwrap();
+        assert_eq!(decoded, map);
+
+        // Re-encoding the decoded UTF-16 content yields the same bytes
+        let mut reencoded = Vec::new();
+        decoded
+            .encode_with_options(&mut reencoded, &options)
+            .unwrap();
+        assert_eq!(reencoded, utf16);
+    }
+}
@@ -0,0 +1,37 @@
+#![cfg(feature = "serde")]
+
+use std::fs;
+
+use rosu_map::{section::hit_objects::HitObjectKind, Beatmap};
+use test_log::test;
+
+#[test]
+fn roundtrip() {
+    for entry in fs::read_dir("./resources").unwrap() {
+        let entry = entry.unwrap();
+        let filename = entry.file_name();
+        let filename = filename.to_str().unwrap();
+
+        if !filename.ends_with(".osu") {
+            continue;
+        }
+
+        let mut map = Beatmap::from_path(entry.path()).unwrap();
+
+        // The curve is skipped during serialization so computing it first
+        // must not make a difference
+        for h in map.hit_objects.iter_mut() {
+            if let HitObjectKind::Slider(ref mut slider) = h.kind {
+                let _ = slider.path.curve();
+            }
+        }
+
+        let json = serde_json::to_string(&map).unwrap();
+        assert!(!json.contains("\"curve\""), "{filename:?}");
+
+        let deserialized: Beatmap = serde_json::from_str(&json)
+            .unwrap_or_else(|e| panic!("Failed to deserialize {filename:?}: {e}"));
+
+        assert_eq!(deserialized, map, "{filename:?}");
+    }
+}